@@ -1,15 +1,36 @@
 // ast: abstract syntax tree definitions for lolcode
 // represents the structure of a lolcode program after parsing
 
+use serde::Serialize;
+
 /// source code position for error reporting
-#[derive(Debug, Clone, PartialEq)]
+///
+/// `start_byte`/`end_byte` are the byte offsets of the single token this
+/// position points at (e.g. a statement's leading keyword, an
+/// identifier) -- not the full span of whatever ast node it's attached
+/// to, since nodes here don't separately track a closing-token position
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct Position {
     pub line: usize,
     pub column: usize,
+    pub start_byte: usize,
+    pub end_byte: usize,
+}
+
+impl Position {
+    /// builds a position pointing at `token`'s span
+    pub fn from_token(token: &crate::types::Token) -> Self {
+        Self {
+            line: token.line,
+            column: token.column,
+            start_byte: token.start_byte,
+            end_byte: token.end_byte,
+        }
+    }
 }
 
 /// expression nodes representing values and operations
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum Expression {
     Number(String, Position),
     String(String, Position),
@@ -55,8 +76,28 @@ pub enum Expression {
     },
 }
 
+impl Expression {
+    /// the position of this expression's leading token -- for a literal or
+    /// identifier that's its own position, for a compound operation it's
+    /// the position of the operation's leading keyword (e.g. `SUM`)
+    pub fn position(&self) -> &Position {
+        match self {
+            Expression::Number(_, pos)
+            | Expression::String(_, pos)
+            | Expression::Identifier(_, pos)
+            | Expression::Sum { pos, .. }
+            | Expression::Diff { pos, .. }
+            | Expression::Produkt { pos, .. }
+            | Expression::Quoshunt { pos, .. }
+            | Expression::Mod { pos, .. }
+            | Expression::BothSaem { pos, .. }
+            | Expression::Diffrint { pos, .. } => pos,
+        }
+    }
+}
+
 /// statement nodes representing actions and control flow
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum Statement {
     /// variable declaration (i has a)
     Declaration {
@@ -83,11 +124,20 @@ pub enum Statement {
         ya_rly: Block,
         no_wai: Option<Block>,
         pos: Position,
+        /// position of the closing OIC, so a NO WAI branch can be inserted
+        /// immediately before it when one is missing
+        oic_pos: Position,
     },
 
     /// loop statement (im in yr loop ... im outta yr loop)
     Loop { body: Block, pos: Position },
 
+    /// loop break statement (gtfo)
+    Gtfo { pos: Position },
+
+    /// input statement (gimmeh) reading a raw yarn into a variable
+    Gimmeh { name: String, pos: Position },
+
     /// standalone expression statement (sets implicit it variable)
     Expr {
         expression: Expression,
@@ -96,13 +146,13 @@ pub enum Statement {
 }
 
 /// block of statements (used in control flow structures)
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct Block {
     pub statements: Vec<Statement>,
 }
 
 /// root program node containing version and body
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct Program {
     pub version: String,
     pub body: Block,
@@ -0,0 +1,101 @@
+// cache: on-disk cache of lint results, keyed by file content, active rule
+// configuration, and crate version, so unchanged files skip re-analysis on
+// repeated ci/local runs
+
+use lol_lint::diagnostic::{Diagnostic, Severity, Span};
+use lol_lint::rules;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+
+/// cached outcome of linting a single file
+#[derive(Serialize, Deserialize)]
+pub struct CachedResult {
+    pub errors: Vec<CachedDiagnostic>,
+    pub warnings: Vec<CachedDiagnostic>,
+    pub warning_total: usize,
+}
+
+/// an on-disk mirror of [`Diagnostic`], with owned fields in place of
+/// `Diagnostic`'s `&'static str` code and applicability: those borrow
+/// from this crate's static rule tables, and there's no safe way to
+/// deserialize a `&'static str` out of an arbitrary on-disk json blob, so
+/// this type exists purely to round-trip through `serde_json` and is
+/// converted to and from a real `Diagnostic` at the cache boundary
+#[derive(Serialize, Deserialize)]
+pub struct CachedDiagnostic {
+    pub code: Option<String>,
+    pub is_error: bool,
+    pub message: String,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub start_byte: Option<usize>,
+    pub end_byte: Option<usize>,
+}
+
+impl From<&Diagnostic> for CachedDiagnostic {
+    fn from(diagnostic: &Diagnostic) -> Self {
+        CachedDiagnostic {
+            code: diagnostic.code.map(str::to_string),
+            is_error: diagnostic.severity == Severity::Error,
+            message: diagnostic.message.clone(),
+            line: diagnostic.span.map(|s| s.line),
+            column: diagnostic.span.and_then(|s| s.column),
+            start_byte: diagnostic.span.and_then(|s| s.start_byte),
+            end_byte: diagnostic.span.and_then(|s| s.end_byte),
+        }
+    }
+}
+
+impl CachedDiagnostic {
+    /// rebuilds a [`Diagnostic`], recovering `code`'s `&'static str` by
+    /// matching it back against [`rules::all`]'s static rule table rather
+    /// than leaking the owned on-disk string
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        let code = self
+            .code
+            .as_deref()
+            .and_then(|code| rules::all().into_iter().find(|rule| rule.code == code))
+            .map(|rule| rule.code);
+        let span = self.line.map(|line| Span {
+            line,
+            column: self.column,
+            start_byte: self.start_byte,
+            end_byte: self.end_byte,
+        });
+        if self.is_error {
+            Diagnostic::error(code, self.message.clone(), span)
+        } else {
+            Diagnostic::warning(code, self.message.clone(), span)
+        }
+    }
+}
+
+/// derives the cache key from a file's content and a fingerprint of the
+/// configuration flags that affect linting output; the crate version is
+/// folded in so upgrading lol-lint invalidates stale entries
+pub fn cache_key(content: &str, config_fingerprint: &str) -> String {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    content.hash(&mut hasher);
+    config_fingerprint.hash(&mut hasher);
+    env!("CARGO_PKG_VERSION").hash(&mut hasher);
+    format!("{:x}", hasher.finish())
+}
+
+/// loads a cached result, if present and parseable
+pub fn load(dir: &Path, key: &str) -> Option<CachedResult> {
+    let data = fs::read_to_string(dir.join(format!("{}.json", key))).ok()?;
+    serde_json::from_str(&data).ok()
+}
+
+/// writes a result to the cache, creating the cache directory if needed;
+/// failures are ignored since the cache is a pure optimization
+pub fn store(dir: &Path, key: &str, result: &CachedResult) {
+    if fs::create_dir_all(dir).is_err() {
+        return;
+    }
+    if let Ok(data) = serde_json::to_string(result) {
+        let _ = fs::write(dir.join(format!("{}.json", key)), data);
+    }
+}
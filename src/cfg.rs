@@ -0,0 +1,181 @@
+// cfg: control-flow graph construction over the ast
+//
+// lowers a program's blocks, conditionals (O RLY?), loops (IM IN YR LOOP),
+// and GTFO into a graph of basic blocks and the edges between them. several
+// lints -- unreachable code, loops that can never terminate, stores that
+// are always overwritten before being read -- need to reason about control
+// flow to be correct rather than a heuristic pass over the ast, and this is
+// the shared graph they walk instead of each reimplementing its own
+// approximation of it
+//
+// lolcode as this parser accepts it has no loop condition (`TIL`/`WILE`
+// clauses aren't part of `ast::Statement::Loop`) and no functions (`HOW
+// DUZ`/`FOUND YR` aren't part of the ast at all), so `IM IN YR LOOP` lowers
+// as an unconditional loop exited only through `GTFO`, and there's nothing
+// here for a call graph -- this module covers exactly the control flow the
+// ast can express today, and should grow if those constructs are added to it
+
+use crate::ast::{Block, Program, Statement};
+
+/// a maximal straight-line run of statements with no branches into or out
+/// of its middle
+#[derive(Debug)]
+pub struct BasicBlock<'a> {
+    pub id: usize,
+    pub statements: Vec<&'a Statement>,
+    /// ids of blocks control can pass to after this one; empty means this
+    /// block never falls through, e.g. it ends in a `GTFO` with no
+    /// enclosing loop, or it's the last block of the program
+    pub successors: Vec<usize>,
+}
+
+/// a program lowered into basic blocks and the edges between them
+#[derive(Debug)]
+pub struct Cfg<'a> {
+    pub blocks: Vec<BasicBlock<'a>>,
+    pub entry: usize,
+}
+
+impl<'a> Cfg<'a> {
+    /// builds the cfg for `program`'s top-level body
+    pub fn build(program: &'a Program) -> Self {
+        let mut builder = Builder { blocks: vec![], loop_exits: vec![] };
+        let entry = builder.new_block();
+        builder.lower_block(&program.body, entry);
+        Cfg { blocks: builder.blocks, entry }
+    }
+
+    /// true if no block anywhere in the graph (other than `id` itself) has
+    /// `id` as a successor -- i.e. no statement in the program can ever
+    /// reach it. the entry block is always reachable by definition
+    pub fn is_unreachable(&self, id: usize) -> bool {
+        id != self.entry
+            && !self
+                .blocks
+                .iter()
+                .any(|block| block.id != id && block.successors.contains(&id))
+    }
+}
+
+struct Builder<'a> {
+    blocks: Vec<BasicBlock<'a>>,
+    /// exit block id for each loop currently being lowered, innermost last;
+    /// what a `GTFO` inside it jumps to
+    loop_exits: Vec<usize>,
+}
+
+impl<'a> Builder<'a> {
+    fn new_block(&mut self) -> usize {
+        let id = self.blocks.len();
+        self.blocks.push(BasicBlock { id, statements: vec![], successors: vec![] });
+        id
+    }
+
+    fn add_edge(&mut self, from: usize, to: usize) {
+        self.blocks[from].successors.push(to);
+    }
+
+    /// lowers `block`'s statements starting in block `current`, returning
+    /// the id of the block control falls through to after the last
+    /// statement, or `None` if every path out of `block` already left
+    /// through a `GTFO`
+    fn lower_block(&mut self, block: &'a Block, mut current: usize) -> Option<usize> {
+        for stmt in &block.statements {
+            match stmt {
+                Statement::Gtfo { .. } => {
+                    self.blocks[current].statements.push(stmt);
+                    if let Some(&exit) = self.loop_exits.last() {
+                        self.add_edge(current, exit);
+                    }
+                    // anything lexically after this GTFO in the same block
+                    // has no incoming edge from here, so it starts a fresh,
+                    // unreachable block rather than continuing this one
+                    current = self.new_block();
+                }
+
+                Statement::ORly { ya_rly, no_wai, .. } => {
+                    let after = self.new_block();
+
+                    let then_start = self.new_block();
+                    self.add_edge(current, then_start);
+                    if let Some(then_end) = self.lower_block(ya_rly, then_start) {
+                        self.add_edge(then_end, after);
+                    }
+
+                    match no_wai {
+                        Some(else_block) => {
+                            let else_start = self.new_block();
+                            self.add_edge(current, else_start);
+                            if let Some(else_end) = self.lower_block(else_block, else_start) {
+                                self.add_edge(else_end, after);
+                            }
+                        }
+                        None => self.add_edge(current, after),
+                    }
+
+                    current = after;
+                }
+
+                Statement::Loop { body, .. } => {
+                    let header = self.new_block();
+                    self.add_edge(current, header);
+
+                    let after = self.new_block();
+                    self.loop_exits.push(after);
+                    if let Some(body_end) = self.lower_block(body, header) {
+                        self.add_edge(body_end, header);
+                    }
+                    self.loop_exits.pop();
+
+                    current = after;
+                }
+
+                _ => self.blocks[current].statements.push(stmt),
+            }
+        }
+        Some(current)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::Lexer;
+    use crate::parser::Parser;
+
+    fn parse(source: &str) -> Program {
+        let tokens = Lexer::new(source.to_string()).tokenize();
+        Parser::new(tokens).parse_program()
+    }
+
+    #[test]
+    fn code_after_an_unconditional_gtfo_is_unreachable() {
+        let program = parse(
+            "HAI 1.2\nIM IN YR LOOP\n    GTFO\n    VISIBLE \"dead\"\nIM OUTTA YR LOOP\nKTHXBYE\n",
+        );
+        let cfg = Cfg::build(&program);
+        // the block created right after the GTFO holds the VISIBLE
+        // statement and has nothing pointing into it
+        let dead_block = cfg
+            .blocks
+            .iter()
+            .find(|b| b.statements.iter().any(|s| matches!(s, Statement::Visible { .. })))
+            .expect("VISIBLE should still be lowered into some block");
+        assert!(cfg.is_unreachable(dead_block.id));
+    }
+
+    #[test]
+    fn the_entry_block_is_always_reachable() {
+        let program = parse("HAI 1.2\nVISIBLE \"hi\"\nKTHXBYE\n");
+        let cfg = Cfg::build(&program);
+        assert!(!cfg.is_unreachable(cfg.entry));
+    }
+
+    #[test]
+    fn a_loop_body_has_a_back_edge_to_its_own_header() {
+        let program = parse("HAI 1.2\nIM IN YR LOOP\n    VISIBLE \"hi\"\nIM OUTTA YR LOOP\nKTHXBYE\n");
+        let cfg = Cfg::build(&program);
+        let header = cfg.blocks.iter().find(|b| cfg.blocks[cfg.entry].successors.contains(&b.id)).unwrap();
+        assert!(header.successors.contains(&header.id));
+    }
+}
@@ -0,0 +1,367 @@
+// codemod: a small pattern-match-and-replace engine over expressions,
+// exposed as a library so other tools in this crate (`rewrite`, and any
+// future structural-search command) can share one matcher instead of
+// each hand-rolling its own, and driving the `lol-lint rewrite`
+// subcommand directly
+//
+// a pattern is an ordinary `Expression` parsed from the same lolcode
+// grammar as everything else, with one extra convention: an identifier
+// spelled `$name` is a metavariable that matches any expression and
+// binds it under `name`. the lexer itself has no notion of `$` -- an
+// identifier is plain alphanumerics -- so `parse_pattern` rewrites each
+// `$name` to a placeholder identifier before handing the text to the
+// real lexer/parser, then renames it back afterward. this means pattern
+// parsing gets the real grammar's precedence and keyword handling for
+// free rather than reimplementing a second, subtly different parser
+//
+// replacement is span-faithful: a bound metavariable in a replacement
+// template is spliced in using its *original source text* (sliced by
+// byte range from the file being rewritten), not reconstructed from the
+// ast, so a captured expression reappears byte-for-byte rather than
+// through a lossy pretty-printer
+
+use crate::ast::Expression;
+use crate::lexer::Lexer;
+use crate::parser::Parser;
+use std::collections::HashMap;
+
+// no underscore: the lexer's `read_word` only accepts
+// `is_ascii_alphanumeric` characters (plus `?`), so a prefix containing
+// `_` would end the identifier early and leave a stray `_name` behind
+const META_PREFIX: &str = "ZZCODEMODMETA";
+
+/// parses `text` as a pattern expression, treating any `$name` as a
+/// metavariable
+pub fn parse_pattern(text: &str) -> Result<Expression, String> {
+    let placeholder_source = rewrite_metavariables(text);
+    let tokens = Lexer::new(placeholder_source).tokenize();
+    let mut parser = Parser::new(tokens);
+    let Some(expr) = parser.parse_expression() else {
+        let messages: Vec<String> = parser.errors.into_iter().map(|d| d.message).collect();
+        return Err(if messages.is_empty() {
+            format!("could not parse pattern '{text}'")
+        } else {
+            messages.join("; ")
+        });
+    };
+    Ok(rename_metavariables(expr))
+}
+
+/// replaces each `$name` with `ZZCODEMODMETA_name`, a valid identifier
+/// the real lexer will tokenize just like any other variable name
+fn rewrite_metavariables(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '$' {
+            out.push_str(META_PREFIX);
+            while let Some(&next) = chars.peek() {
+                if next.is_ascii_alphanumeric() {
+                    out.push(next);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// walks a freshly parsed pattern and turns every `ZZCODEMODMETA_name`
+/// identifier back into `Expression::Identifier("$name", ..)` -- the
+/// lexer could never produce a `$`-prefixed identifier on its own, so
+/// this doubles as the tag `is_metavariable` checks for
+fn rename_metavariables(expr: Expression) -> Expression {
+    match expr {
+        Expression::Identifier(name, pos) => {
+            if let Some(stripped) = name.strip_prefix(META_PREFIX) {
+                Expression::Identifier(format!("${stripped}"), pos)
+            } else {
+                Expression::Identifier(name, pos)
+            }
+        }
+        Expression::Sum { left, right, pos } => Expression::Sum {
+            left: Box::new(rename_metavariables(*left)),
+            right: Box::new(rename_metavariables(*right)),
+            pos,
+        },
+        Expression::Diff { left, right, pos } => Expression::Diff {
+            left: Box::new(rename_metavariables(*left)),
+            right: Box::new(rename_metavariables(*right)),
+            pos,
+        },
+        Expression::Produkt { left, right, pos } => Expression::Produkt {
+            left: Box::new(rename_metavariables(*left)),
+            right: Box::new(rename_metavariables(*right)),
+            pos,
+        },
+        Expression::Quoshunt { left, right, pos } => Expression::Quoshunt {
+            left: Box::new(rename_metavariables(*left)),
+            right: Box::new(rename_metavariables(*right)),
+            pos,
+        },
+        Expression::Mod { left, right, pos } => Expression::Mod {
+            left: Box::new(rename_metavariables(*left)),
+            right: Box::new(rename_metavariables(*right)),
+            pos,
+        },
+        Expression::BothSaem { left, right, pos } => Expression::BothSaem {
+            left: Box::new(rename_metavariables(*left)),
+            right: Box::new(rename_metavariables(*right)),
+            pos,
+        },
+        Expression::Diffrint { left, right, pos } => Expression::Diffrint {
+            left: Box::new(rename_metavariables(*left)),
+            right: Box::new(rename_metavariables(*right)),
+            pos,
+        },
+        literal @ (Expression::Number(..) | Expression::String(..)) => literal,
+    }
+}
+
+fn is_metavariable(expr: &Expression) -> Option<&str> {
+    match expr {
+        Expression::Identifier(name, _) => name.strip_prefix('$'),
+        _ => None,
+    }
+}
+
+/// attempts to match `pattern` against `candidate`, recording each
+/// metavariable's binding; a metavariable bound more than once (the
+/// same `$x` appearing twice in a pattern) must bind the same shape
+/// both times, ignoring source position
+pub fn match_pattern<'a>(pattern: &Expression, candidate: &'a Expression) -> Option<HashMap<String, &'a Expression>> {
+    let mut bindings = HashMap::new();
+    if match_into(pattern, candidate, &mut bindings) {
+        Some(bindings)
+    } else {
+        None
+    }
+}
+
+fn match_into<'a>(
+    pattern: &Expression,
+    candidate: &'a Expression,
+    bindings: &mut HashMap<String, &'a Expression>,
+) -> bool {
+    if let Some(name) = is_metavariable(pattern) {
+        return match bindings.get(name) {
+            Some(existing) => expr_eq_ignore_pos(existing, candidate),
+            None => {
+                bindings.insert(name.to_string(), candidate);
+                true
+            }
+        };
+    }
+
+    match (pattern, candidate) {
+        (Expression::Number(a, _), Expression::Number(b, _)) => a == b,
+        (Expression::String(a, _), Expression::String(b, _)) => a == b,
+        (Expression::Identifier(a, _), Expression::Identifier(b, _)) => a == b,
+        (Expression::Sum { left: pl, right: pr, .. }, Expression::Sum { left: cl, right: cr, .. })
+        | (Expression::Diff { left: pl, right: pr, .. }, Expression::Diff { left: cl, right: cr, .. })
+        | (Expression::Produkt { left: pl, right: pr, .. }, Expression::Produkt { left: cl, right: cr, .. })
+        | (Expression::Quoshunt { left: pl, right: pr, .. }, Expression::Quoshunt { left: cl, right: cr, .. })
+        | (Expression::Mod { left: pl, right: pr, .. }, Expression::Mod { left: cl, right: cr, .. })
+        | (Expression::BothSaem { left: pl, right: pr, .. }, Expression::BothSaem { left: cl, right: cr, .. })
+        | (Expression::Diffrint { left: pl, right: pr, .. }, Expression::Diffrint { left: cl, right: cr, .. }) => {
+            match_into(pl, cl, bindings) && match_into(pr, cr, bindings)
+        }
+        _ => false,
+    }
+}
+
+/// structural equality of two expressions, ignoring `Position` --
+/// `Expression` derives `PartialEq` off every field including position,
+/// which would make the same variable read at two different lines
+/// compare unequal
+fn expr_eq_ignore_pos(a: &Expression, b: &Expression) -> bool {
+    match (a, b) {
+        (Expression::Number(a, _), Expression::Number(b, _)) => a == b,
+        (Expression::String(a, _), Expression::String(b, _)) => a == b,
+        (Expression::Identifier(a, _), Expression::Identifier(b, _)) => a == b,
+        (Expression::Sum { left: al, right: ar, .. }, Expression::Sum { left: bl, right: br, .. })
+        | (Expression::Diff { left: al, right: ar, .. }, Expression::Diff { left: bl, right: br, .. })
+        | (Expression::Produkt { left: al, right: ar, .. }, Expression::Produkt { left: bl, right: br, .. })
+        | (Expression::Quoshunt { left: al, right: ar, .. }, Expression::Quoshunt { left: bl, right: br, .. })
+        | (Expression::Mod { left: al, right: ar, .. }, Expression::Mod { left: bl, right: br, .. })
+        | (Expression::BothSaem { left: al, right: ar, .. }, Expression::BothSaem { left: bl, right: br, .. })
+        | (Expression::Diffrint { left: al, right: ar, .. }, Expression::Diffrint { left: bl, right: br, .. }) => {
+            expr_eq_ignore_pos(al, bl) && expr_eq_ignore_pos(ar, br)
+        }
+        _ => false,
+    }
+}
+
+/// the byte range `expr` was parsed from, including every child --
+/// `Expression::position()` only points at the node's own leading
+/// token, so a compound expression's true span is the widest range
+/// covered by any of its parts
+pub fn expr_span(expr: &Expression) -> (usize, usize) {
+    let pos = expr.position();
+    let (mut start, mut end) = (pos.start_byte, pos.end_byte);
+    let mut widen = |child: &Expression| {
+        let (child_start, child_end) = expr_span(child);
+        start = start.min(child_start);
+        end = end.max(child_end);
+    };
+    match expr {
+        Expression::Sum { left, right, .. }
+        | Expression::Diff { left, right, .. }
+        | Expression::Produkt { left, right, .. }
+        | Expression::Quoshunt { left, right, .. }
+        | Expression::Mod { left, right, .. }
+        | Expression::BothSaem { left, right, .. }
+        | Expression::Diffrint { left, right, .. } => {
+            widen(left);
+            widen(right);
+        }
+        Expression::Number(..) | Expression::String(..) | Expression::Identifier(..) => {}
+    }
+    (start, end)
+}
+
+/// renders `pattern` back into lolcode source text, splicing each bound
+/// metavariable in as the literal source text captured for it (from
+/// `bindings_text`, keyed without the leading `$`) rather than
+/// reconstructing it from its ast -- the "span-faithful" half of a
+/// rewrite. returns an error naming the first metavariable used in the
+/// replacement that the match never bound
+pub fn render_replacement(pattern: &Expression, bindings_text: &HashMap<String, String>) -> Result<String, String> {
+    if let Some(name) = is_metavariable(pattern) {
+        return bindings_text
+            .get(name)
+            .cloned()
+            .ok_or_else(|| format!("replacement uses unbound metavariable ${name}"));
+    }
+    match pattern {
+        Expression::Number(n, _) => Ok(n.clone()),
+        Expression::String(s, _) => Ok(format!("\"{s}\"")),
+        Expression::Identifier(name, _) => Ok(name.clone()),
+        Expression::Sum { left, right, .. } => binop_text("SUM OF", left, right, bindings_text),
+        Expression::Diff { left, right, .. } => binop_text("DIFF OF", left, right, bindings_text),
+        Expression::Produkt { left, right, .. } => binop_text("PRODUKT OF", left, right, bindings_text),
+        Expression::Quoshunt { left, right, .. } => binop_text("QUOSHUNT OF", left, right, bindings_text),
+        Expression::Mod { left, right, .. } => binop_text("MOD OF", left, right, bindings_text),
+        Expression::BothSaem { left, right, .. } => binop_text("BOTH SAEM", left, right, bindings_text),
+        Expression::Diffrint { left, right, .. } => binop_text("DIFFRINT", left, right, bindings_text),
+    }
+}
+
+fn binop_text(
+    keyword: &str,
+    left: &Expression,
+    right: &Expression,
+    bindings_text: &HashMap<String, String>,
+) -> Result<String, String> {
+    let left = render_replacement(left, bindings_text)?;
+    let right = render_replacement(right, bindings_text)?;
+    Ok(format!("{keyword} {left} AN {right}"))
+}
+
+/// visits every expression node in `program`'s statements, including
+/// nested `O RLY?`/`IM IN YR LOOP` bodies and every sub-expression of a
+/// compound expression -- a match can occur anywhere in the tree, not
+/// just at a statement's top-level expression
+pub fn visit_expressions<'a>(program: &'a crate::ast::Program, f: &mut impl FnMut(&'a Expression)) {
+    visit_block(&program.body, f);
+}
+
+fn visit_block<'a>(block: &'a crate::ast::Block, f: &mut impl FnMut(&'a Expression)) {
+    use crate::ast::Statement;
+    for stmt in &block.statements {
+        match stmt {
+            Statement::Declaration { value, .. } | Statement::Assignment { value, .. } => {
+                if let Some(expr) = value {
+                    visit_expr(expr, f);
+                }
+            }
+            Statement::Visible { expressions, .. } => {
+                for expr in expressions {
+                    visit_expr(expr, f);
+                }
+            }
+            Statement::ORly { ya_rly, no_wai, .. } => {
+                visit_block(ya_rly, f);
+                if let Some(no_wai) = no_wai {
+                    visit_block(no_wai, f);
+                }
+            }
+            Statement::Loop { body, .. } => visit_block(body, f),
+            Statement::Expr { expression, .. } => visit_expr(expression, f),
+            Statement::Gtfo { .. } | Statement::Gimmeh { .. } => {}
+        }
+    }
+}
+
+fn visit_expr<'a>(expr: &'a Expression, f: &mut impl FnMut(&'a Expression)) {
+    f(expr);
+    match expr {
+        Expression::Sum { left, right, .. }
+        | Expression::Diff { left, right, .. }
+        | Expression::Produkt { left, right, .. }
+        | Expression::Quoshunt { left, right, .. }
+        | Expression::Mod { left, right, .. }
+        | Expression::BothSaem { left, right, .. }
+        | Expression::Diffrint { left, right, .. } => {
+            visit_expr(left, f);
+            visit_expr(right, f);
+        }
+        Expression::Number(..) | Expression::String(..) | Expression::Identifier(..) => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn match_pattern_binds_a_metavariable() {
+        let pattern = parse_pattern("SUM OF $a AN 1").unwrap();
+        let candidate = parse_pattern("SUM OF 5 AN 1").unwrap();
+        let bindings = match_pattern(&pattern, &candidate).unwrap();
+        assert!(matches!(bindings.get("a"), Some(Expression::Number(n, _)) if n == "5"));
+    }
+
+    #[test]
+    fn match_pattern_rejects_a_non_matching_candidate() {
+        let pattern = parse_pattern("SUM OF $a AN 1").unwrap();
+        let candidate = parse_pattern("SUM OF 5 AN 2").unwrap();
+        assert!(match_pattern(&pattern, &candidate).is_none());
+    }
+
+    #[test]
+    fn rewrites_a_matched_expression_using_its_original_source_text() {
+        let source = "HAI 1.2\nVISIBLE SUM OF x AN 1\nKTHXBYE\n";
+        let pattern = parse_pattern("SUM OF $a AN 1").unwrap();
+        let replace = parse_pattern("DIFF OF $a AN 1").unwrap();
+
+        let tokens = crate::lexer::Lexer::new(source.to_string()).tokenize();
+        let program = crate::parser::Parser::new(tokens).parse_program();
+
+        let mut found = None;
+        visit_expressions(&program, &mut |candidate| {
+            if found.is_some() {
+                return;
+            }
+            if let Some(bindings) = match_pattern(&pattern, candidate) {
+                found = Some((expr_span(candidate), bindings));
+            }
+        });
+        let ((start, end), bindings) = found.expect("pattern should match");
+        let bindings_text: HashMap<String, String> = bindings
+            .into_iter()
+            .map(|(name, expr)| {
+                let (bstart, bend) = expr_span(expr);
+                (name, source[bstart..bend].to_string())
+            })
+            .collect();
+
+        let replacement = render_replacement(&replace, &bindings_text).unwrap();
+        let rewritten = format!("{}{}{}", &source[..start], replacement, &source[end..]);
+        assert_eq!(rewritten, "HAI 1.2\nVISIBLE DIFF OF x AN 1\nKTHXBYE\n");
+    }
+}
@@ -0,0 +1,176 @@
+// compare: diffs two `--json` reports produced by earlier lol-lint runs,
+// so CI can flag genuinely new problems separately from ones that were
+// already there before the current change
+
+use colored::*;
+use serde::Deserialize;
+use std::collections::BTreeSet;
+
+/// the subset of a `--json` report's shape that compare actually needs;
+/// kept separate from `JsonReport` so this doesn't have to grow
+/// `Deserialize` (and the awkward owned-string plumbing that would bring)
+/// on every struct in the main report just to support this one command
+#[derive(Deserialize)]
+struct ComparableReport {
+    files: Vec<ComparableFile>,
+}
+
+#[derive(Deserialize)]
+struct ComparableFile {
+    file: String,
+    #[serde(default)]
+    errors: Vec<String>,
+    #[serde(default)]
+    warnings: Vec<String>,
+}
+
+/// a single diagnostic identified by the file it's in, its severity, and
+/// its message text; two runs agree a diagnostic is "the same" if all
+/// three match, since diagnostics carry no other stable identity
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone)]
+struct Fingerprint {
+    file: String,
+    severity: &'static str,
+    message: String,
+}
+
+fn load(path: &str) -> Result<BTreeSet<Fingerprint>, String> {
+    let text =
+        std::fs::read_to_string(path).map_err(|e| format!("could not read '{}': {}", path, e))?;
+    let report: ComparableReport =
+        serde_json::from_str(&text).map_err(|e| format!("could not parse '{}': {}", path, e))?;
+
+    let mut set = BTreeSet::new();
+    for file in report.files {
+        for message in file.errors {
+            set.insert(Fingerprint {
+                file: file.file.clone(),
+                severity: "error",
+                message,
+            });
+        }
+        for message in file.warnings {
+            set.insert(Fingerprint {
+                file: file.file.clone(),
+                severity: "warning",
+                message,
+            });
+        }
+    }
+    Ok(set)
+}
+
+/// compares two `--json` reports and prints newly introduced, fixed, and
+/// persisting diagnostics; returns the process exit code (1 if any
+/// diagnostic is new, so CI can fail a build that introduces regressions
+/// without also failing on pre-existing debt)
+pub fn run(old_path: &str, new_path: &str) -> i32 {
+    let old = match load(old_path) {
+        Ok(set) => set,
+        Err(e) => {
+            eprintln!("{} {}", "error:".red().bold(), e);
+            return 2;
+        }
+    };
+    let new = match load(new_path) {
+        Ok(set) => set,
+        Err(e) => {
+            eprintln!("{} {}", "error:".red().bold(), e);
+            return 2;
+        }
+    };
+
+    let introduced: Vec<&Fingerprint> = new.difference(&old).collect();
+    let fixed: Vec<&Fingerprint> = old.difference(&new).collect();
+    let persisting: Vec<&Fingerprint> = old.intersection(&new).collect();
+
+    if !introduced.is_empty() {
+        println!("{}", format!("Introduced ({}):", introduced.len()).red().bold());
+        for f in &introduced {
+            println!("  {}: {}: {}", f.file, f.severity, f.message);
+        }
+    }
+    if !fixed.is_empty() {
+        println!("{}", format!("Fixed ({}):", fixed.len()).green().bold());
+        for f in &fixed {
+            println!("  {}: {}: {}", f.file, f.severity, f.message);
+        }
+    }
+    if !persisting.is_empty() {
+        println!(
+            "{}",
+            format!("Persisting ({}):", persisting.len()).yellow()
+        );
+        for f in &persisting {
+            println!("  {}: {}: {}", f.file, f.severity, f.message);
+        }
+    }
+    if introduced.is_empty() && fixed.is_empty() && persisting.is_empty() {
+        println!("No diagnostics in either report.");
+    }
+
+    if introduced.is_empty() {
+        0
+    } else {
+        1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    struct ScratchFile {
+        path: std::path::PathBuf,
+    }
+
+    impl ScratchFile {
+        fn new(contents: &str) -> Self {
+            let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+            let path = std::env::temp_dir().join(format!("lol_lint_compare_test_{nanos}.json"));
+            std::fs::write(&path, contents).unwrap();
+            Self { path }
+        }
+        fn path_str(&self) -> String {
+            self.path.to_string_lossy().into_owned()
+        }
+    }
+
+    impl Drop for ScratchFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+
+    fn report(warnings: &[&str]) -> String {
+        let messages: Vec<String> = warnings.iter().map(|w| format!("{w:?}")).collect();
+        format!(r#"{{"files":[{{"file":"a.lol","errors":[],"warnings":[{}]}}]}}"#, messages.join(","))
+    }
+
+    #[test]
+    fn a_warning_present_in_both_reports_is_persisting_not_introduced() {
+        let old = ScratchFile::new(&report(&["unused variable 'x'"]));
+        let new = ScratchFile::new(&report(&["unused variable 'x'"]));
+        assert_eq!(run(&old.path_str(), &new.path_str()), 0);
+    }
+
+    #[test]
+    fn a_new_warning_absent_from_the_old_report_exits_one() {
+        let old = ScratchFile::new(&report(&[]));
+        let new = ScratchFile::new(&report(&["unused variable 'x'"]));
+        assert_eq!(run(&old.path_str(), &new.path_str()), 1);
+    }
+
+    #[test]
+    fn a_warning_fixed_since_the_old_report_still_exits_zero() {
+        let old = ScratchFile::new(&report(&["unused variable 'x'"]));
+        let new = ScratchFile::new(&report(&[]));
+        assert_eq!(run(&old.path_str(), &new.path_str()), 0);
+    }
+
+    #[test]
+    fn an_unreadable_report_path_exits_two() {
+        assert_eq!(run("/nonexistent/old.json", "/nonexistent/new.json"), 2);
+    }
+}
@@ -0,0 +1,33 @@
+// config: linter configuration for optional, opt-in checks
+// most rules here are style preferences rather than correctness issues,
+// so they default to off and are enabled individually or via a preset
+
+use serde::{Deserialize, Serialize};
+
+/// configuration controlling which optional lint rules are active
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LintConfig {
+    /// minimum identifier length in characters (0 disables the check)
+    pub min_identifier_length: usize,
+    /// maximum identifier length in characters (0 disables the check)
+    pub max_identifier_length: usize,
+    /// flag trailing whitespace and mixed tab/space indentation
+    pub check_whitespace: bool,
+    /// minimum percentage of comment lines to code lines (0.0 disables)
+    pub min_comment_density: f64,
+    /// require declarations to appear before other statements in a block
+    pub declarations_at_top: bool,
+}
+
+impl LintConfig {
+    /// bundles the recommended style checks enabled by `--style`
+    pub fn style_preset() -> Self {
+        Self {
+            min_identifier_length: 2,
+            max_identifier_length: 60,
+            check_whitespace: true,
+            min_comment_density: 5.0,
+            declarations_at_top: true,
+        }
+    }
+}
@@ -0,0 +1,236 @@
+// daemon: a long-running lint server for editors and build systems that
+// would otherwise pay lol-lint's process-startup cost on every file --
+// binds a unix domain socket and answers newline-delimited json-rpc
+// requests, keeping hierarchical config resolution and the on-disk lint
+// cache warm across requests instead of re-walking/re-opening them fresh
+// every time
+//
+// this crate has no string interner or other genuinely "interned data"
+// to keep warm; the closest honest analogue is the per-directory
+// `.lollint.toml` resolution below, which is pure and otherwise gets
+// redone on every single request
+//
+// speaks newline-delimited json-rpc rather than `lsp`'s `Content-Length`
+// framing, since this is for tooling that already frames its own
+// transport (a build system's rpc client) rather than a raw stdio editor
+// extension expecting the lsp wire format
+
+use lol_lint::config::LintConfig;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// per-directory `.lollint.toml` resolution, shared across every
+/// connection and request so it's only ever computed once per directory
+type ConfigCache = Mutex<HashMap<PathBuf, LintConfig>>;
+
+/// entry point for the `daemon` subcommand: `lol-lint daemon --socket <path>`.
+/// binds `<path>` as a unix domain socket and serves `lint` requests until
+/// a client sends `shutdown` or the process is killed
+pub fn run(args: &[String]) -> i32 {
+    let Some(socket_path) = parse_socket_arg(args) else {
+        eprintln!("usage: lol-lint daemon --socket <path>");
+        return 2;
+    };
+
+    // a stale socket file from a daemon that didn't shut down cleanly
+    // would otherwise make `bind` fail with "address in use"
+    let _ = std::fs::remove_file(&socket_path);
+    let listener = match UnixListener::bind(&socket_path) {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("error: could not bind socket '{socket_path}': {e}");
+            return 2;
+        }
+    };
+
+    let config_cache: Arc<ConfigCache> = Arc::new(Mutex::new(HashMap::new()));
+    let cache_dir = PathBuf::from(".lol-lint-cache");
+
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else { continue };
+        let config_cache = Arc::clone(&config_cache);
+        let cache_dir = cache_dir.clone();
+        std::thread::spawn(move || handle_connection(stream, &config_cache, &cache_dir));
+    }
+
+    0
+}
+
+fn parse_socket_arg(args: &[String]) -> Option<String> {
+    args.iter().position(|a| a == "--socket").and_then(|i| args.get(i + 1)).cloned()
+}
+
+/// reads one json-rpc request per line from `stream` and writes one
+/// json-rpc response per line back, until the client disconnects or sends
+/// `shutdown`
+fn handle_connection(stream: UnixStream, config_cache: &ConfigCache, cache_dir: &Path) {
+    let Ok(mut writer) = stream.try_clone() else { return };
+    let reader = BufReader::new(stream);
+
+    for line in reader.lines() {
+        let Ok(line) = line else { break };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let Ok(request) = serde_json::from_str::<Value>(&line) else {
+            continue;
+        };
+        let id = request.get("id").cloned().unwrap_or(Value::Null);
+
+        let response = match request.get("method").and_then(Value::as_str) {
+            Some("lint") => handle_lint(&request, config_cache, cache_dir),
+            Some("shutdown") => {
+                let _ = write_line(&mut writer, &json!({ "jsonrpc": "2.0", "id": id, "result": "ok" }));
+                // a shared listener has no clean way to unblock its own
+                // `accept` from another thread without extra plumbing
+                // this small a daemon doesn't otherwise need; exiting the
+                // whole process is the honest stopping point
+                std::process::exit(0);
+            }
+            _ => json!({ "jsonrpc": "2.0", "id": id, "error": { "code": -32601, "message": "method not found" } }),
+        };
+
+        if write_line(&mut writer, &response).is_err() {
+            break;
+        }
+    }
+}
+
+/// answers a `lint` request: `params.path` is read from disk, linted with
+/// its resolved `.lollint.toml` config (cached per directory), and the
+/// result cached the same way `--cache` does for the cli
+fn handle_lint(request: &Value, config_cache: &ConfigCache, cache_dir: &Path) -> Value {
+    let id = request.get("id").cloned().unwrap_or(Value::Null);
+    let Some(path) = request.pointer("/params/path").and_then(Value::as_str) else {
+        return json!({ "jsonrpc": "2.0", "id": id, "error": { "code": -32602, "message": "missing params.path" } });
+    };
+
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) => {
+            let message = format!("could not read '{path}': {e}");
+            return json!({ "jsonrpc": "2.0", "id": id, "error": { "code": -32000, "message": message } });
+        }
+    };
+
+    let dir = Path::new(path).parent().unwrap_or_else(|| Path::new(".")).to_path_buf();
+    let config = {
+        let mut cache = config_cache.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        cache
+            .entry(dir)
+            .or_insert_with(|| {
+                let mut config = LintConfig::default();
+                crate::hierconfig::apply(Path::new(path), &mut config);
+                config
+            })
+            .clone()
+    };
+
+    let fingerprint = format!(
+        "{}|{}|{}|{}|{}",
+        config.min_identifier_length,
+        config.max_identifier_length,
+        config.check_whitespace,
+        config.min_comment_density,
+        config.declarations_at_top,
+    );
+    let key = crate::cache::cache_key(&content, &fingerprint);
+
+    if let Some(cached) = crate::cache::load(cache_dir, &key) {
+        let errors: Vec<String> = cached.errors.iter().map(|d| d.message.clone()).collect();
+        let warnings: Vec<String> = cached.warnings.iter().map(|d| d.message.clone()).collect();
+        return json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "result": { "errors": errors, "warnings": warnings }
+        });
+    }
+
+    let result = lol_lint::lint_source(&content, &config);
+    crate::cache::store(
+        cache_dir,
+        &key,
+        &crate::cache::CachedResult {
+            errors: result.errors.iter().map(crate::cache::CachedDiagnostic::from).collect(),
+            warnings: result.warnings.iter().map(crate::cache::CachedDiagnostic::from).collect(),
+            warning_total: result.warnings.len(),
+        },
+    );
+
+    let errors: Vec<String> = result.errors.iter().map(|d| d.message.clone()).collect();
+    let warnings: Vec<String> = result.warnings.iter().map(|d| d.message.clone()).collect();
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "result": { "errors": errors, "warnings": warnings }
+    })
+}
+
+fn write_line(writer: &mut UnixStream, value: &Value) -> std::io::Result<()> {
+    let mut body = serde_json::to_string(value).unwrap_or_default();
+    body.push('\n');
+    writer.write_all(body.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    #[test]
+    fn parse_socket_arg_reads_the_path_after_the_flag() {
+        let args = vec!["--socket".to_string(), "/tmp/lol.sock".to_string()];
+        assert_eq!(parse_socket_arg(&args), Some("/tmp/lol.sock".to_string()));
+    }
+
+    #[test]
+    fn parse_socket_arg_is_none_without_the_flag() {
+        assert_eq!(parse_socket_arg(&[]), None);
+    }
+
+    struct ScratchFile {
+        path: std::path::PathBuf,
+    }
+
+    impl ScratchFile {
+        fn new(contents: &str) -> Self {
+            let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+            let path = std::env::temp_dir().join(format!("lol_lint_daemon_test_{nanos}.lol"));
+            std::fs::write(&path, contents).unwrap();
+            Self { path }
+        }
+        fn path_str(&self) -> String {
+            self.path.to_string_lossy().into_owned()
+        }
+    }
+
+    impl Drop for ScratchFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+
+    #[test]
+    fn handle_lint_reports_a_missing_declaration_for_a_real_file() {
+        let file = ScratchFile::new("HAI 1.2\nVISIBLE x\nKTHXBYE\n");
+        let cache_dir = std::env::temp_dir().join("lol_lint_daemon_test_cache");
+        let config_cache: ConfigCache = Mutex::new(HashMap::new());
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": "lint", "params": { "path": file.path_str() } });
+        let response = handle_lint(&request, &config_cache, &cache_dir);
+        assert_eq!(response["id"], 1);
+        assert!(response["result"]["warnings"].as_array().is_some());
+    }
+
+    #[test]
+    fn handle_lint_reports_missing_params_path_as_an_error() {
+        let cache_dir = std::env::temp_dir().join("lol_lint_daemon_test_cache");
+        let config_cache: ConfigCache = Mutex::new(HashMap::new());
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": "lint", "params": {} });
+        let response = handle_lint(&request, &config_cache, &cache_dir);
+        assert_eq!(response["error"]["code"], -32602);
+    }
+}
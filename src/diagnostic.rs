@@ -0,0 +1,103 @@
+// diagnostic: the structured shape a lint check reports through
+//
+// `Linter` builds these directly rather than assembling a plain string
+// and letting callers re-parse it later; `message` still carries the
+// exact human-readable text (severity word, description, and any
+// position) that lol-lint has always printed, so every existing output
+// format keeps working unchanged off `Diagnostic::to_string`, while
+// `code`, `severity`, and `span` are known at the source and carried
+// through as-is by every consumer that needs structure -- `--sort`,
+// `--group-by`, `--list-rules`, and the lsp server all read these
+// fields directly instead of re-deriving them from `message`.
+
+use serde::Serialize;
+
+/// how serious a diagnostic is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// a 1-based source position a diagnostic points at; `column` is `None`
+/// for the handful of diagnostics (e.g. an unused suppression comment)
+/// that only know which line they concern. `start_byte`/`end_byte`
+/// mirror `ast::Position`'s byte range of the offending token, and are
+/// `None` whenever `column` is, since both come from the same lack of a
+/// specific token to point at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Span {
+    pub line: usize,
+    pub column: Option<usize>,
+    pub start_byte: Option<usize>,
+    pub end_byte: Option<usize>,
+}
+
+impl Span {
+    /// builds a span from an ast position's line/column and byte range
+    pub fn from_position(pos: &crate::ast::Position) -> Self {
+        Span {
+            line: pos.line,
+            column: Some(pos.column),
+            start_byte: Some(pos.start_byte),
+            end_byte: Some(pos.end_byte),
+        }
+    }
+
+    /// builds a span that only knows a line number, e.g. from a raw-text
+    /// check with no ast position to draw a byte range from
+    pub fn line_only(line: usize) -> Self {
+        Span { line, column: None, start_byte: None, end_byte: None }
+    }
+}
+
+/// one issue a lint check found
+#[derive(Debug, Clone, Serialize)]
+pub struct Diagnostic {
+    /// the rule that produced this, e.g. `Some("LL001")`; `None` for
+    /// diagnostics not tied to a specific rule code
+    pub code: Option<&'static str>,
+    pub severity: Severity,
+    /// the full human-readable text, exactly as every output format has
+    /// always printed it (severity word and position included)
+    pub message: String,
+    pub span: Option<Span>,
+    /// supplementary detail beyond `message`; no check populates this yet,
+    /// but the field exists so one can attach it without another type change
+    pub notes: Vec<String>,
+    /// the machine-applicable edit for this diagnostic, when one exists.
+    /// always `None` coming out of `Linter` today, since `--fix` computes
+    /// suggestions in a separate pass against source bytes rather than
+    /// during linting; reserved here for that pass to fill in later
+    pub suggestion: Option<crate::fix::Suggestion>,
+}
+
+impl Diagnostic {
+    pub fn error(code: Option<&'static str>, message: String, span: Option<Span>) -> Self {
+        Diagnostic {
+            code,
+            severity: Severity::Error,
+            message,
+            span,
+            notes: vec![],
+            suggestion: None,
+        }
+    }
+
+    pub fn warning(code: Option<&'static str>, message: String, span: Option<Span>) -> Self {
+        Diagnostic {
+            code,
+            severity: Severity::Warning,
+            message,
+            span,
+            notes: vec![],
+            suggestion: None,
+        }
+    }
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
@@ -0,0 +1,137 @@
+// editorconfig: minimal .editorconfig reader for `fmt`
+//
+// only understands the four properties `fmt` acts on -- indent_style,
+// indent_size, end_of_line, and insert_final_newline -- and only the two
+// section patterns realistic for a lolcode project, `[*]` and `[*.lol]`;
+// full glob matching and every other editorconfig property are out of
+// scope for a formatter this small.
+
+use std::path::Path;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum IndentStyle {
+    Space,
+    Tab,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum EndOfLine {
+    Lf,
+    Crlf,
+    Cr,
+}
+
+/// the formatting-relevant settings `fmt` honors, resolved from every
+/// `.editorconfig` between the filesystem root and the linted file's
+/// directory
+#[derive(Clone, Copy)]
+pub struct EditorConfig {
+    pub indent_style: IndentStyle,
+    pub indent_size: usize,
+    pub end_of_line: EndOfLine,
+    pub insert_final_newline: bool,
+}
+
+impl Default for EditorConfig {
+    fn default() -> Self {
+        Self {
+            indent_style: IndentStyle::Space,
+            indent_size: 4,
+            end_of_line: EndOfLine::Lf,
+            insert_final_newline: true,
+        }
+    }
+}
+
+impl EditorConfig {
+    /// the literal text for one nesting level of indentation
+    pub fn indent_unit(&self) -> String {
+        match self.indent_style {
+            IndentStyle::Space => " ".repeat(self.indent_size),
+            IndentStyle::Tab => "\t".to_string(),
+        }
+    }
+
+    pub fn newline(&self) -> &'static str {
+        match self.end_of_line {
+            EndOfLine::Lf => "\n",
+            EndOfLine::Crlf => "\r\n",
+            EndOfLine::Cr => "\r",
+        }
+    }
+}
+
+/// walks upward from `file`'s directory, applying every `.editorconfig`
+/// found along the way root-to-leaf so the closest file wins, and
+/// stopping the search entirely once a file declares `root = true`
+pub fn resolve(file: &Path) -> EditorConfig {
+    let mut dirs = Vec::new();
+    let mut current = file.parent();
+    while let Some(dir) = current {
+        dirs.push(dir.to_path_buf());
+        current = dir.parent();
+    }
+
+    let mut applicable = Vec::new();
+    for dir in &dirs {
+        let Ok(text) = std::fs::read_to_string(dir.join(".editorconfig")) else {
+            continue;
+        };
+        let is_root = text
+            .lines()
+            .any(|l| l.split('=').map(str::trim).eq(["root", "true"]));
+        applicable.push(text);
+        if is_root {
+            break;
+        }
+    }
+    applicable.reverse();
+
+    let mut config = EditorConfig::default();
+    for text in &applicable {
+        apply(text, &mut config);
+    }
+    config
+}
+
+/// applies the `[*]`/`[*.lol]` sections of one `.editorconfig` file's
+/// text onto `config`, in place
+fn apply(text: &str, config: &mut EditorConfig) {
+    let mut in_matching_section = false;
+    for raw_line in text.lines() {
+        let line = raw_line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if let Some(pattern) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            in_matching_section = pattern == "*" || pattern == "*.lol";
+            continue;
+        }
+        if !in_matching_section {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let (key, value) = (key.trim(), value.trim());
+        match key {
+            "indent_style" => {
+                config.indent_style = if value == "tab" { IndentStyle::Tab } else { IndentStyle::Space };
+            }
+            "indent_size" => {
+                if let Ok(size) = value.parse() {
+                    config.indent_size = size;
+                }
+            }
+            "end_of_line" => {
+                config.end_of_line = match value {
+                    "crlf" => EndOfLine::Crlf,
+                    "cr" => EndOfLine::Cr,
+                    _ => EndOfLine::Lf,
+                };
+            }
+            "insert_final_newline" => config.insert_final_newline = value == "true",
+            _ => {}
+        }
+    }
+}
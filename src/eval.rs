@@ -0,0 +1,29 @@
+// eval: folds a single bare lolcode expression from the command line,
+// e.g. `lol-lint eval "SUM OF 2 AN PRODUKT OF 3 AN 4"`, and prints its
+// value and type -- for checking operator precedence and testing the
+// same folding logic `run` uses, without writing a whole `.lol` file
+
+use crate::interpreter::eval_expression;
+use lol_lint::lexer::Lexer;
+use lol_lint::parser::Parser;
+use std::collections::HashMap;
+
+pub fn run(args: &[String]) -> i32 {
+    let Some(source) = args.first() else {
+        eprintln!("usage: lol-lint eval \"<expression>\"");
+        return 2;
+    };
+
+    let tokens = Lexer::new(source.clone()).tokenize();
+    let mut parser = Parser::new(tokens);
+    let Some(expr) = parser.parse_expression() else {
+        for error in &parser.errors {
+            eprintln!("error: {}", error.message);
+        }
+        return 2;
+    };
+
+    let value = eval_expression(&expr, &HashMap::new());
+    println!("{value} : {}", value.type_name());
+    0
+}
@@ -0,0 +1,68 @@
+// ffi: a C-compatible entry point so editors and tools written in C/C++
+// (or anything else with an FFI) can embed the linter in-process instead
+// of shelling out to the `lol-lint` binary and parsing its stdout
+//
+// built as part of the `cdylib` crate-type behind the `ffi` feature:
+//
+//     cargo build --release --lib --features ffi
+
+use std::ffi::{c_char, CStr, CString};
+
+use crate::config::LintConfig;
+
+/// lints `source` and returns its diagnostics as a json-encoded
+/// [`crate::LintResult`], both as owned, nul-terminated C strings. `config`
+/// is a json-encoded [`LintConfig`]; a null pointer, or a string that isn't
+/// valid json, falls back to [`LintConfig::default`]. the returned pointer
+/// is heap-allocated by this library and must be passed to
+/// [`lol_lint_free_string`] to be freed -- freeing it any other way, or
+/// leaking it, are both the caller's responsibility to avoid
+///
+/// # Safety
+///
+/// `source` must be a valid, nul-terminated C string. `config` must be
+/// either null or a valid, nul-terminated C string. both must remain valid
+/// for the duration of this call
+#[no_mangle]
+pub unsafe extern "C" fn lol_lint_run(
+    source: *const c_char,
+    config: *const c_char,
+) -> *mut c_char {
+    let source = match CStr::from_ptr(source).to_str() {
+        Ok(source) => source,
+        Err(_) => return std::ptr::null_mut(),
+    };
+
+    let config = if config.is_null() {
+        LintConfig::default()
+    } else {
+        CStr::from_ptr(config)
+            .to_str()
+            .ok()
+            .and_then(|json| serde_json::from_str(json).ok())
+            .unwrap_or_default()
+    };
+
+    let result = crate::lint_source(source, &config);
+    let json = serde_json::to_string(&result).unwrap_or_default();
+
+    match CString::new(json) {
+        Ok(json) => json.into_raw(),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// frees a string previously returned by [`lol_lint_run`]. calling this on
+/// any other pointer, or calling it twice on the same pointer, is
+/// undefined behavior
+///
+/// # Safety
+///
+/// `ptr` must either be null or a pointer previously returned by
+/// [`lol_lint_run`] that has not already been freed
+#[no_mangle]
+pub unsafe extern "C" fn lol_lint_free_string(ptr: *mut c_char) {
+    if !ptr.is_null() {
+        drop(CString::from_raw(ptr));
+    }
+}
@@ -0,0 +1,242 @@
+// fix: automatic correction for `--fix`
+//
+// `suggestions_for` covers the two purely mechanical formatting issues
+// LL016/LL017 report directly against the source text: trailing
+// whitespace and a missing final newline. `suggestions_for_unused_declarations`
+// covers LL004, which needs the AST rather than the raw text to know
+// where a declaration starts and ends. everything else lol-lint flags
+// needs a human, since it's semantic rather than textual.
+
+use serde::Serialize;
+
+/// a machine-applicable edit for one diagnostic, keyed by that
+/// diagnostic's exact message text so `--json` consumers can match it back
+/// up without threading a `Suggestion` through `Diagnostic` itself
+#[derive(Serialize, Clone, Debug)]
+pub struct Suggestion {
+    pub message: String,
+    pub start_byte: usize,
+    pub end_byte: usize,
+    pub replacement: String,
+    pub applicability: &'static str,
+}
+
+/// computes the suggestion for every trailing-whitespace or
+/// missing-final-newline diagnostic `check_whitespace_style` would report
+/// against `source`, so `--json` can carry them without re-deriving
+/// positions from the message text
+pub fn suggestions_for(source: &str) -> Vec<Suggestion> {
+    let mut suggestions = Vec::new();
+    if source.is_empty() {
+        return suggestions;
+    }
+
+    let mut offset = 0;
+    for (i, raw_line) in source.split_inclusive('\n').enumerate() {
+        let line_no = i + 1;
+        let line = raw_line.strip_suffix('\n').unwrap_or(raw_line);
+        let trimmed = line.trim_end_matches([' ', '\t']);
+        if trimmed.len() != line.len() {
+            suggestions.push(Suggestion {
+                message: format!("warning: trailing whitespace (line {})", line_no),
+                start_byte: offset + trimmed.len(),
+                end_byte: offset + line.len(),
+                replacement: String::new(),
+                applicability: "MachineApplicable",
+            });
+        }
+        offset += raw_line.len();
+    }
+
+    if !source.ends_with('\n') {
+        suggestions.push(Suggestion {
+            message: "warning: file is missing a final newline".to_string(),
+            start_byte: source.len(),
+            end_byte: source.len(),
+            replacement: "\n".to_string(),
+            applicability: "MachineApplicable",
+        });
+    }
+
+    suggestions
+}
+
+/// computes the suggestion for every declared-but-never-used variable
+/// `linter` found safe to autofix (see `Linter::unused_declarations` --
+/// GIMMEH-tainted variables are excluded there, since deleting their
+/// declaration would silently drop a read from stdin), by deleting the
+/// variable's whole `I HAS A ...` line, including its trailing newline
+pub fn suggestions_for_unused_declarations(
+    source: &str,
+    unused: &[(String, crate::ast::Position)],
+) -> Vec<Suggestion> {
+    let line_start_bytes = line_start_bytes(source);
+
+    unused
+        .iter()
+        .filter_map(|(message, pos)| {
+            let start = *line_start_bytes.get(pos.line - 1)?;
+            let end = line_start_bytes
+                .get(pos.line)
+                .copied()
+                .unwrap_or(source.len());
+            Some(Suggestion {
+                message: message.clone(),
+                start_byte: start,
+                end_byte: end,
+                replacement: String::new(),
+                applicability: "MachineApplicable",
+            })
+        })
+        .collect()
+}
+
+/// computes the suggestion for every "declared twice" error
+/// `Linter::duplicate_declarations` found safe to autofix, rewriting the
+/// second `I HAS A x ITZ v` into the assignment `x R v` that's almost
+/// always what was meant -- replaces everything from the declaration's
+/// leading `I` up to the initializer expression, which is left verbatim
+pub fn suggestions_for_duplicate_declarations(
+    source: &str,
+    duplicates: &[crate::linter::DuplicateDeclarationFix],
+) -> Vec<Suggestion> {
+    let line_start_bytes = line_start_bytes(source);
+
+    duplicates
+        .iter()
+        .filter_map(|dup| {
+            let decl_line_start = *line_start_bytes.get(dup.decl_pos.line - 1)?;
+            let start = decl_line_start + dup.decl_pos.column - 1;
+            let value_line_start = *line_start_bytes.get(dup.value_pos.line - 1)?;
+            let end = value_line_start + dup.value_pos.column - 1;
+            Some(Suggestion {
+                message: dup.message.clone(),
+                start_byte: start,
+                end_byte: end,
+                replacement: format!("{} R ", dup.name),
+                applicability: "MachineApplicable",
+            })
+        })
+        .collect()
+}
+
+/// computes the suggestion for every miscased keyword
+/// `Linter::check_keyword_casing` found, replacing just that token's exact
+/// span with its correct casing -- lolcode keywords are always ASCII, so
+/// `column - 1` is also its byte offset into the line
+pub fn suggestions_for_keyword_casing(
+    source: &str,
+    issues: &[crate::linter::KeywordCasingIssue],
+) -> Vec<Suggestion> {
+    let line_start_bytes = line_start_bytes(source);
+
+    issues
+        .iter()
+        .filter_map(|issue| {
+            let line_start = *line_start_bytes.get(issue.pos.line - 1)?;
+            let start = line_start + issue.pos.column - 1;
+            Some(Suggestion {
+                message: issue.message(),
+                start_byte: start,
+                end_byte: start + issue.written.len(),
+                replacement: issue.correct.clone(),
+                applicability: "MachineApplicable",
+            })
+        })
+        .collect()
+}
+
+/// computes the suggestion for every O RLY? block
+/// `Linter::missing_no_wai` found missing a NO WAI branch, inserting an
+/// empty one with a placeholder comment right before the closing OIC,
+/// indented to match the surrounding block
+pub fn suggestions_for_missing_no_wai(
+    source: &str,
+    missing: &[crate::linter::MissingNoWaiFix],
+) -> Vec<Suggestion> {
+    let line_start_bytes = line_start_bytes(source);
+
+    missing
+        .iter()
+        .filter_map(|fix| {
+            let oic_line_start = *line_start_bytes.get(fix.oic_pos.line - 1)?;
+            let insert_at = oic_line_start;
+            let pad = " ".repeat(fix.indent);
+            let replacement = format!(
+                "{pad}NO WAI\n{pad}    BTW handle other case\n"
+            );
+            Some(Suggestion {
+                message: fix.message.clone(),
+                start_byte: insert_at,
+                end_byte: insert_at,
+                replacement,
+                applicability: "MachineApplicable",
+            })
+        })
+        .collect()
+}
+
+/// byte offset of the start of each line in `source`, indexed by
+/// (line number - 1); shared by the AST-driven suggestion builders, which
+/// only have line/column positions to work with, not byte spans
+fn line_start_bytes(source: &str) -> Vec<usize> {
+    let mut starts = vec![0];
+    for (i, ch) in source.char_indices() {
+        if ch == '\n' {
+            starts.push(i + 1);
+        }
+    }
+    starts
+}
+
+/// splices `suggestions` into `source`, replacing each `[start_byte,
+/// end_byte)` span with its replacement text; suggestions are applied from
+/// the end of the file backward so earlier byte offsets stay valid as
+/// later ones are consumed. `suggestions` need not be sorted or cover
+/// every available fix — this is also how `--fix --interactive` applies
+/// only the subset the user accepted
+pub fn apply_suggestions(source: &str, suggestions: &[Suggestion]) -> String {
+    let mut ordered: Vec<&Suggestion> = suggestions.iter().collect();
+    ordered.sort_by_key(|s| s.start_byte);
+
+    let mut content = source.to_string();
+    for suggestion in ordered.into_iter().rev() {
+        content.replace_range(suggestion.start_byte..suggestion.end_byte, &suggestion.replacement);
+    }
+    content
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn suggestions_for_trims_trailing_whitespace_and_adds_final_newline() {
+        let source = "VISIBLE 1   \nVISIBLE 2";
+        let suggestions = suggestions_for(source);
+        assert_eq!(suggestions.len(), 2);
+        let fixed = apply_suggestions(source, &suggestions);
+        assert_eq!(fixed, "VISIBLE 1\nVISIBLE 2\n");
+    }
+
+    #[test]
+    fn suggestions_for_unused_declarations_deletes_the_whole_line() {
+        let source = "HAI 1.2\nI HAS A x ITZ 1\nVISIBLE \"hi\"\nKTHXBYE\n";
+        let unused = vec![(
+            "warning: variable 'x' declared but never used (line 2, column 1)".to_string(),
+            crate::ast::Position { line: 2, column: 1, start_byte: 8, end_byte: 9 },
+        )];
+        let suggestions = suggestions_for_unused_declarations(source, &unused);
+        let fixed = apply_suggestions(source, &suggestions);
+        assert_eq!(fixed, "HAI 1.2\nVISIBLE \"hi\"\nKTHXBYE\n");
+    }
+
+    #[test]
+    fn apply_suggestions_only_applies_the_given_subset() {
+        let source = "VISIBLE 1  \nVISIBLE 2  \n";
+        let all = suggestions_for(source);
+        assert_eq!(all.len(), 2);
+        let fixed = apply_suggestions(source, &all[..1]);
+        assert_eq!(fixed, "VISIBLE 1\nVISIBLE 2  \n");
+    }
+}
@@ -0,0 +1,197 @@
+// fmt: canonical formatter for lolcode source
+//
+// rewrites each file into a consistent shape: one indentation level per
+// block nesting depth (four spaces by default, overridable per
+// `.editorconfig`), keywords in their required uppercase spelling
+// (already guaranteed by the lexer -- a miscased keyword tokenizes as a
+// plain identifier rather than a `Keyword` token, so there's nothing left
+// to normalize here), a single space between every token including
+// around AN/OF, and comments carried through unchanged. this works
+// directly off the token stream rather than the ast, so it can reformat a
+// file the parser would reject.
+
+use crate::editorconfig::EditorConfig;
+use colored::*;
+use lol_lint::lexer::Lexer;
+use lol_lint::types::{Token, TokenKind};
+use std::path::Path;
+
+/// re-renders `source` into lol-lint's canonical formatting, using
+/// `config`'s indent unit, line ending, and final-newline preference
+pub fn format_source(source: &str, config: &EditorConfig) -> String {
+    let tokens = Lexer::new(source.to_string()).tokenize();
+
+    // split the token stream into source lines; an empty line stays empty
+    // rather than being collapsed, so blank lines separating sections
+    // survive formatting
+    let mut lines: Vec<Vec<Token>> = vec![vec![]];
+    for token in tokens {
+        if let TokenKind::Newline = token.kind {
+            lines.push(vec![]);
+        } else {
+            lines.last_mut().unwrap().push(token);
+        }
+    }
+
+    // O RLY? and YA RLY/NO WAI both nest a level deeper than their
+    // header: YA RLY/NO WAI sit one level under O RLY?/OIC, and their own
+    // bodies sit one level under *that*. So OIC unwinds two levels (its
+    // own branch body, then the O RLY? itself), while NO WAI unwinds one
+    // level (the YA RLY branch it follows) before opening its own
+    let indent_unit = config.indent_unit();
+    let mut depth = 0usize;
+    let mut rendered_lines = Vec::with_capacity(lines.len());
+    for line in &lines {
+        if line.is_empty() {
+            rendered_lines.push(String::new());
+            continue;
+        }
+
+        let pre_dedent = if starts_with(line, &["OIC"]) {
+            2
+        } else if starts_with(line, &["NO", "WAI"]) || starts_with(line, &["IM", "OUTTA"]) {
+            1
+        } else {
+            0
+        };
+        depth = depth.saturating_sub(pre_dedent);
+
+        let indent = indent_unit.repeat(depth);
+        rendered_lines.push(format!("{indent}{}", render_line(line)));
+
+        if starts_with(line, &["O", "RLY?"])
+            || starts_with(line, &["YA", "RLY"])
+            || starts_with(line, &["NO", "WAI"])
+            || starts_with(line, &["IM", "IN"])
+        {
+            depth += 1;
+        }
+    }
+
+    let mut result = rendered_lines.join(config.newline());
+    if config.insert_final_newline {
+        if !result.ends_with(config.newline()) {
+            result.push_str(config.newline());
+        }
+    } else {
+        while result.ends_with(config.newline()) {
+            result.truncate(result.len() - config.newline().len());
+        }
+    }
+    result
+}
+
+/// whether `line` begins with exactly this sequence of keywords
+fn starts_with(line: &[Token], keywords: &[&str]) -> bool {
+    keywords.iter().enumerate().all(|(i, kw)| {
+        matches!(&line.get(i).map(|t| &t.kind), Some(TokenKind::Keyword(k)) if k == kw)
+    })
+}
+
+/// joins one line's tokens with a single space between each, which is
+/// also what normalizes spacing around AN/OF -- they're ordinary keyword
+/// tokens like any other
+fn render_line(line: &[Token]) -> String {
+    line.iter().map(render_token).collect::<Vec<_>>().join(" ")
+}
+
+fn render_token(token: &Token) -> String {
+    match &token.kind {
+        TokenKind::Keyword(k) => k.clone(),
+        TokenKind::Identifier(s) => s.clone(),
+        TokenKind::Number(s) => s.clone(),
+        TokenKind::StringLiteral(s) => format!("\"{s}\""),
+        // a multiline obtw's content contains embedded newlines the
+        // lexer never turned into `Newline` tokens, so it renders as a
+        // single (multi-line) piece of this line rather than several
+        TokenKind::Comment(s) => {
+            if s.contains('\n') {
+                format!("OBTW{s}TLDR")
+            } else {
+                format!("BTW{s}")
+            }
+        }
+        TokenKind::Newline => String::new(),
+    }
+}
+
+/// entry point for the `fmt` subcommand: rewrites each file in place with
+/// canonical formatting, or with `--check`, only reports which files
+/// aren't already formatted without writing anything -- for CI to enforce
+/// formatting alongside linting. mirrors `compare`/`merge`'s self-contained
+/// argument handling, since fmt's file-list shape doesn't fit the shared
+/// `Cli` struct used for `check`
+pub fn run(args: &[String]) -> i32 {
+    let check = args.iter().any(|a| a == "--check");
+    let files: Vec<&String> = args.iter().filter(|a| a.as_str() != "--check").collect();
+
+    if files.is_empty() {
+        eprintln!("usage: lol-lint fmt [--check] <file.lol>...");
+        return 2;
+    }
+
+    let mut had_error = false;
+    let mut unformatted = false;
+    for file in files {
+        let source = match std::fs::read_to_string(file) {
+            Ok(s) => s,
+            Err(e) => {
+                eprintln!("error: could not read '{file}': {e}");
+                had_error = true;
+                continue;
+            }
+        };
+
+        let config = crate::editorconfig::resolve(Path::new(file));
+        let formatted = format_source(&source, &config);
+        if formatted == source {
+            continue;
+        }
+
+        if check {
+            unformatted = true;
+            println!("{}", format!("--- a/{file}").bold());
+            println!("{}", format!("+++ b/{file}").bold());
+            print_diff(&source, &formatted);
+        } else if let Err(e) = std::fs::write(file, &formatted) {
+            eprintln!("error: could not write '{file}': {e}");
+            had_error = true;
+        } else {
+            println!("formatted {file}");
+        }
+    }
+
+    if had_error {
+        2
+    } else if check && unformatted {
+        1
+    } else {
+        0
+    }
+}
+
+/// prints a plain by-index unified-diff-style comparison of `original`
+/// against `formatted`; a real line diff (see `main`'s `unified_diff`,
+/// used for `--fix --emit-patch`) isn't needed here since this is just a
+/// human-readable preview for `--check`, not something meant to be
+/// applied with `git apply`
+fn print_diff(original: &str, formatted: &str) {
+    let before: Vec<&str> = original.lines().collect();
+    let after: Vec<&str> = formatted.lines().collect();
+
+    for i in 0..before.len().max(after.len()) {
+        let old = before.get(i).copied();
+        let new = after.get(i).copied();
+        if old == new {
+            continue;
+        }
+        let line_no = i + 1;
+        println!("{}", format!("@@ -{line_no} +{line_no} @@").cyan());
+        if let Some(old) = old {
+            println!("{}", format!("-{old}").red());
+        }
+        if let Some(new) = new {
+            println!("{}", format!("+{new}").green());
+        }
+    }
+}
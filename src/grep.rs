@@ -0,0 +1,241 @@
+// grep: `lol-lint grep '<pattern>' [--replace '<template>'] [--dry-run]
+// <file.lol>...`, structural search over the ast using `codemod`'s
+// matcher -- for auditing a codebase for a shape no built-in rule
+// covers, e.g. `lol-lint grep 'QUOSHUNT OF $x AN 0'` to find every
+// division whose divisor happens to be a literal zero, metavariable and
+// all, without writing a new lint rule for it
+//
+// `--replace` turns a search into a one-off codemod: every match becomes
+// a `fix::Suggestion` (the same machine-applicable-edit type `--fix`
+// already applies for LL004/LL016/LL017) and `fix::apply_suggestions`
+// rewrites the file in place. `--dry-run` previews the result as a diff
+// instead of writing, mirroring `check --fix --dry-run`'s pairing
+//
+// without `--replace`, exit code follows real `grep`'s convention: 0
+// means at least one match, 1 means the search ran cleanly but found
+// nothing, 2 means an error (bad pattern, unreadable file) stopped it
+// before it could search
+
+use colored::*;
+use lol_lint::codemod;
+use lol_lint::fix::{self, Suggestion};
+use lol_lint::lexer::Lexer;
+use lol_lint::parser::Parser;
+
+pub fn run(args: &[String]) -> i32 {
+    let replace_text = flag_value(args, "--replace");
+    let dry_run = args.iter().any(|a| a == "--dry-run");
+
+    let mut positionals = Vec::new();
+    let mut skip_next = false;
+    for arg in args {
+        if skip_next {
+            skip_next = false;
+            continue;
+        }
+        if arg == "--replace" {
+            skip_next = true;
+            continue;
+        }
+        if arg == "--dry-run" {
+            continue;
+        }
+        positionals.push(arg);
+    }
+
+    let usage = "usage: lol-lint grep '<pattern>' [--replace '<template>'] [--dry-run] <file.lol>...";
+    if positionals.len() < 2 {
+        eprintln!("{usage}");
+        return 2;
+    }
+    let pattern_text = positionals[0];
+    let files = &positionals[1..];
+
+    let pattern = match codemod::parse_pattern(pattern_text) {
+        Ok(pattern) => pattern,
+        Err(e) => {
+            eprintln!("error: bad pattern '{pattern_text}': {e}");
+            return 2;
+        }
+    };
+    let replace = match replace_text.map(codemod::parse_pattern) {
+        Some(Ok(replace)) => Some(replace),
+        Some(Err(e)) => {
+            eprintln!("error: bad replace template '{}': {e}", replace_text.unwrap());
+            return 2;
+        }
+        None => None,
+    };
+
+    let mut match_count = 0;
+    let mut had_error = false;
+    for file in files {
+        let source = match std::fs::read_to_string(file) {
+            Ok(source) => source,
+            Err(e) => {
+                eprintln!("error: could not read '{file}': {e}");
+                had_error = true;
+                continue;
+            }
+        };
+
+        let tokens = Lexer::new(source.clone()).tokenize();
+        let mut parser = Parser::new(tokens);
+        let program = parser.parse_program();
+
+        let mut suggestions = Vec::new();
+        let mut render_error = None;
+        codemod::visit_expressions(&program, &mut |candidate| {
+            let Some(bindings) = codemod::match_pattern(&pattern, candidate) else {
+                return;
+            };
+            let (start, end) = codemod::expr_span(candidate);
+            match_count += 1;
+
+            let Some(replace) = &replace else {
+                let pos = candidate.position();
+                println!("{file}:{}:{}: {}", pos.line, pos.column, &source[start..end]);
+                return;
+            };
+            if render_error.is_some() {
+                return;
+            }
+            let bindings_text = bindings
+                .into_iter()
+                .map(|(name, expr)| {
+                    let (bstart, bend) = codemod::expr_span(expr);
+                    (name, source[bstart..bend].to_string())
+                })
+                .collect();
+            match codemod::render_replacement(replace, &bindings_text) {
+                Ok(replacement) => suggestions.push(Suggestion {
+                    message: format!("grep --replace match at {}:{}", candidate.position().line, candidate.position().column),
+                    start_byte: start,
+                    end_byte: end,
+                    replacement,
+                    applicability: "MachineApplicable",
+                }),
+                Err(e) => render_error = Some(e),
+            }
+        });
+
+        if let Some(e) = render_error {
+            eprintln!("error: {file}: {e}");
+            had_error = true;
+            continue;
+        }
+        if suggestions.is_empty() || replace.is_none() {
+            continue;
+        }
+
+        let fixed = fix::apply_suggestions(&source, &suggestions);
+        if dry_run {
+            println!("{}", format!("--- a/{file}").bold());
+            println!("{}", format!("+++ b/{file}").bold());
+            print_diff(&source, &fixed);
+        } else if let Err(e) = std::fs::write(file, &fixed) {
+            eprintln!("error: could not write '{file}': {e}");
+            had_error = true;
+        } else {
+            println!("rewrote {} match(es) in {file}", suggestions.len());
+        }
+    }
+
+    if had_error {
+        2
+    } else if replace.is_some() {
+        0
+    } else if match_count == 0 {
+        1
+    } else {
+        0
+    }
+}
+
+fn flag_value<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    args.iter().position(|a| a == name).and_then(|i| args.get(i + 1)).map(String::as_str)
+}
+
+/// same plain by-index preview `fmt --check` prints -- a human-readable
+/// diff, not something meant to be applied with `git apply`
+fn print_diff(original: &str, rewritten: &str) {
+    let before: Vec<&str> = original.lines().collect();
+    let after: Vec<&str> = rewritten.lines().collect();
+
+    for i in 0..before.len().max(after.len()) {
+        let old = before.get(i).copied();
+        let new = after.get(i).copied();
+        if old == new {
+            continue;
+        }
+        let line_no = i + 1;
+        println!("{}", format!("@@ -{line_no} +{line_no} @@").cyan());
+        if let Some(old) = old {
+            println!("{}", format!("-{old}").red());
+        }
+        if let Some(new) = new {
+            println!("{}", format!("+{new}").green());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    /// a scratch `.lol` file under the system temp dir, removed on drop --
+    /// `grep::run` only knows how to read/write real files, so exercising
+    /// it end to end needs one on disk rather than a source string
+    struct ScratchFile {
+        path: std::path::PathBuf,
+    }
+
+    impl ScratchFile {
+        fn new(contents: &str) -> Self {
+            let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+            let path = std::env::temp_dir().join(format!("lol_lint_grep_test_{nanos}.lol"));
+            std::fs::write(&path, contents).unwrap();
+            Self { path }
+        }
+        fn path_str(&self) -> String {
+            self.path.to_string_lossy().into_owned()
+        }
+        fn read(&self) -> String {
+            std::fs::read_to_string(&self.path).unwrap()
+        }
+    }
+
+    impl Drop for ScratchFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+
+    #[test]
+    fn finds_a_matching_expression_and_exits_zero() {
+        let file = ScratchFile::new("HAI 1.2\nVISIBLE QUOSHUNT OF 7 AN 0\nKTHXBYE\n");
+        let args = vec!["QUOSHUNT OF $x AN 0".to_string(), file.path_str()];
+        assert_eq!(run(&args), 0);
+    }
+
+    #[test]
+    fn exits_one_when_the_pattern_does_not_match_anything() {
+        let file = ScratchFile::new("HAI 1.2\nVISIBLE SUM OF 1 AN 2\nKTHXBYE\n");
+        let args = vec!["QUOSHUNT OF $x AN 0".to_string(), file.path_str()];
+        assert_eq!(run(&args), 1);
+    }
+
+    #[test]
+    fn replace_rewrites_the_matched_expression_in_place() {
+        let file = ScratchFile::new("HAI 1.2\nVISIBLE SUM OF x AN 1\nKTHXBYE\n");
+        let args = vec![
+            "--replace".to_string(),
+            "DIFF OF $a AN 1".to_string(),
+            "SUM OF $a AN 1".to_string(),
+            file.path_str(),
+        ];
+        assert_eq!(run(&args), 0);
+        assert!(file.read().contains("DIFF OF x AN 1"));
+    }
+}
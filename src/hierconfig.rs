@@ -0,0 +1,61 @@
+// hierconfig: hierarchical .lollint.toml discovery and merging
+// searches upward from the linted file to the filesystem root, then
+// applies any configs found root-to-leaf so the closest file to the
+// linted source wins on any setting it specifies
+
+use lol_lint::config::LintConfig;
+use serde::Deserialize;
+use std::path::Path;
+
+/// partial config as parsed from a `.lollint.toml`; every field is
+/// optional so a file only needs to specify the settings it overrides
+#[derive(Deserialize, Default)]
+struct PartialConfig {
+    min_identifier_length: Option<usize>,
+    max_identifier_length: Option<usize>,
+    check_whitespace: Option<bool>,
+    min_comment_density: Option<f64>,
+    declarations_at_top: Option<bool>,
+}
+
+impl PartialConfig {
+    fn apply_to(&self, config: &mut LintConfig) {
+        if let Some(v) = self.min_identifier_length {
+            config.min_identifier_length = v;
+        }
+        if let Some(v) = self.max_identifier_length {
+            config.max_identifier_length = v;
+        }
+        if let Some(v) = self.check_whitespace {
+            config.check_whitespace = v;
+        }
+        if let Some(v) = self.min_comment_density {
+            config.min_comment_density = v;
+        }
+        if let Some(v) = self.declarations_at_top {
+            config.declarations_at_top = v;
+        }
+    }
+}
+
+/// finds every `.lollint.toml` between the filesystem root and the
+/// directory containing `file`, and merges them into `config` in that
+/// order, so a nested project's config overrides its parent's
+pub fn apply(file: &Path, config: &mut LintConfig) {
+    let mut dirs = Vec::new();
+    let mut current = file.parent();
+    while let Some(dir) = current {
+        dirs.push(dir.to_path_buf());
+        current = dir.parent();
+    }
+    dirs.reverse();
+
+    for dir in dirs {
+        let Ok(text) = std::fs::read_to_string(dir.join(".lollint.toml")) else {
+            continue;
+        };
+        if let Ok(partial) = toml::from_str::<PartialConfig>(&text) {
+            partial.apply_to(config);
+        }
+    }
+}
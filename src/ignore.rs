@@ -0,0 +1,41 @@
+// ignore: gitignore-style pattern filtering for directory and glob expansion
+// supports glob patterns (`vendor/*.lol`) and bare names that match any
+// path component (`vendor`), like a gitignore line with no slash; this is
+// not a full gitignore implementation (no negation, no anchoring rules)
+
+use glob::Pattern;
+use std::path::Path;
+
+/// loads patterns from a `.lollintignore` file, one per line, skipping
+/// blank lines and `#` comments; returns an empty list if the file is absent
+pub fn load_ignore_file(path: &Path) -> Vec<String> {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return vec![];
+    };
+
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
+
+/// returns true if the given path matches any of the ignore patterns
+pub fn is_ignored(path: &str, patterns: &[String]) -> bool {
+    let components: Vec<&str> = path.split('/').collect();
+
+    patterns.iter().any(|pattern| {
+        let Ok(glob_pattern) = Pattern::new(pattern) else {
+            return false;
+        };
+
+        if glob_pattern.matches(path) {
+            return true;
+        }
+
+        // a pattern with no slash matches at any path depth, mirroring
+        // gitignore's handling of bare names like `vendor` or `*.gen.lol`
+        !pattern.contains('/') && components.iter().any(|c| glob_pattern.matches(c))
+    })
+}
@@ -0,0 +1,553 @@
+// interpreter: a tree-walking evaluator for the subset of lolcode this
+// parser understands, driving `lol-lint run <file.lol>` -- so a program
+// can be checked against its actual runtime behavior with the same front
+// end that lints it, and so a `--fix` rewrite can be sanity-checked
+// against real output instead of just being re-linted
+//
+// `HOW DUZ`/`FOUND YR` (function definitions) and `I IZ ... MKAY` (calls)
+// have no ast representation in this parser at all (see `cfg`'s doc
+// comment for the same gap affecting control-flow analysis) -- there's
+// nothing here to execute a function body with, so this interpreter
+// covers everything the ast *does* represent (VISIBLE, GIMMEH,
+// arithmetic, O RLY?, IM IN YR LOOP) and stops there rather than
+// fabricating function support the parser doesn't have
+
+use colored::*;
+use lol_lint::ast::{Block, Expression, Program, Statement};
+use lol_lint::lexer::Lexer;
+use lol_lint::parser::Parser;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::io::BufRead;
+
+/// entry point for the `run` subcommand:
+/// `lol-lint run [--max-steps N] [--max-output BYTES] [--timeout SECS] [--no-io] [--coverage] <file.lol>`.
+/// the three limits, plus `--no-io`, exist so a grading pipeline can execute
+/// an untrusted student submission without it hanging on stdin, looping
+/// forever, or flooding stdout -- none of which the interpreter would
+/// otherwise notice on its own. `--coverage` is for the same audience from
+/// the other direction: seeing which statements a given test input actually
+/// exercised
+pub fn run(args: &[String]) -> i32 {
+    let no_io = args.iter().any(|a| a == "--no-io");
+    let coverage = args.iter().any(|a| a == "--coverage");
+    let max_steps = flag_value(args, "--max-steps").and_then(|v| v.parse().ok());
+    let max_output = flag_value(args, "--max-output").and_then(|v| v.parse().ok());
+    let timeout_secs: Option<u64> = flag_value(args, "--timeout").and_then(|v| v.parse().ok());
+
+    let mut files = Vec::new();
+    let mut skip_next = false;
+    for arg in args {
+        if skip_next {
+            skip_next = false;
+            continue;
+        }
+        if matches!(arg.as_str(), "--max-steps" | "--max-output" | "--timeout") {
+            skip_next = true;
+            continue;
+        }
+        if matches!(arg.as_str(), "--no-io" | "--coverage") {
+            continue;
+        }
+        files.push(arg);
+    }
+
+    let Some(file) = files.first() else {
+        eprintln!(
+            "usage: lol-lint run [--max-steps N] [--max-output BYTES] [--timeout SECS] [--no-io] [--coverage] <file.lol>"
+        );
+        return 2;
+    };
+
+    let source = match std::fs::read_to_string(file) {
+        Ok(source) => source,
+        Err(e) => {
+            eprintln!("error: could not read '{file}': {e}");
+            return 2;
+        }
+    };
+
+    let tokens = Lexer::new(source.clone()).tokenize();
+    let mut parser = Parser::new(tokens);
+    let program = parser.parse_program();
+    if !parser.errors.is_empty() {
+        for error in &parser.errors {
+            eprintln!("error: {}", error.message);
+        }
+        return 2;
+    }
+
+    let limits = Limits { max_steps, max_output, no_io, coverage };
+
+    // the step/output limits are enforced by the interpreter itself as it
+    // runs, so they stop it deterministically; a wall-clock timeout can't
+    // be enforced that way against a genuinely infinite loop (there's no
+    // safe way to kill a running thread), so it's layered on top the same
+    // way `run_lint_pipeline_with_timeout` bounds a pathological lint pass:
+    // the interpreter runs on a background thread and the main thread
+    // simply stops waiting for it once the deadline passes
+    let exit_code = if let Some(timeout_secs) = timeout_secs {
+        let program_for_thread = program.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let _ = tx.send(execute(program_for_thread, limits));
+        });
+        match rx.recv_timeout(std::time::Duration::from_secs(timeout_secs)) {
+            Ok((exit_code, hits)) => (exit_code, hits),
+            Err(_) => {
+                eprintln!("error: execution timed out after {timeout_secs}s");
+                (3, HashMap::new())
+            }
+        }
+    } else {
+        execute(program.clone(), limits)
+    };
+
+    let (exit_code, hits) = exit_code;
+    if coverage {
+        report_coverage(file, &source, &program, &hits);
+    }
+    exit_code
+}
+
+fn execute(program: Program, limits: Limits) -> (i32, HashMap<usize, u32>) {
+    let mut interpreter = Interpreter::new(limits);
+    interpreter.run(&program);
+    let exit_code = match interpreter.halted {
+        Some(reason) => {
+            eprintln!("error: {reason}");
+            3
+        }
+        None => 0,
+    };
+    (exit_code, interpreter.hits)
+}
+
+fn flag_value<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    args.iter().position(|a| a == name).and_then(|i| args.get(i + 1)).map(String::as_str)
+}
+
+/// sandboxing limits for a single `run`; each is optional and independent
+#[derive(Clone, Copy)]
+struct Limits {
+    max_steps: Option<usize>,
+    max_output: Option<usize>,
+    /// disables `GIMMEH` so untrusted input can't block a grading pipeline
+    /// waiting on a stdin that will never arrive; a `GIMMEH` under
+    /// `--no-io` reads as an immediate empty `YARN` instead
+    no_io: bool,
+    coverage: bool,
+}
+
+/// writes `<file>.lcov` alongside `file` and prints `source` annotated
+/// with a per-line hit count, covering every statement `collect_coverable_lines`
+/// found in `program` -- a line that statement collection never visits (a
+/// comment, a blank line, `HAI`/`KTHXBYE`) is left unannotated rather than
+/// reported as uncovered, since it was never something a test input could
+/// have exercised in the first place
+fn report_coverage(file: &str, source: &str, program: &Program, hits: &HashMap<usize, u32>) {
+    let mut coverable = BTreeSet::new();
+    collect_coverable_lines(&program.body, &mut coverable);
+
+    let lcov_path = format!("{file}.lcov");
+    let mut lcov = String::new();
+    lcov.push_str("TN:\n");
+    lcov.push_str(&format!("SF:{file}\n"));
+    for line in &coverable {
+        lcov.push_str(&format!("DA:{},{}\n", line, hits.get(line).copied().unwrap_or(0)));
+    }
+    lcov.push_str(&format!(
+        "LH:{}\n",
+        coverable.iter().filter(|line| hits.contains_key(*line)).count()
+    ));
+    lcov.push_str(&format!("LF:{}\n", coverable.len()));
+    lcov.push_str("end_of_record\n");
+    match std::fs::write(&lcov_path, &lcov) {
+        Ok(()) => println!("wrote coverage to {lcov_path}"),
+        Err(e) => eprintln!("error: could not write '{lcov_path}': {e}"),
+    }
+
+    println!("{}", "--- coverage ---".bold());
+    for (i, text) in source.lines().enumerate() {
+        let line_no = i + 1;
+        let marker = match (coverable.contains(&line_no), hits.get(&line_no)) {
+            (false, _) => "    ".normal(),
+            (true, Some(count)) => format!("{count:>4}").green(),
+            (true, None) => "NOT ".red(),
+        };
+        println!("{marker}| {text}");
+    }
+}
+
+/// every line hosting a statement in `block`, including nested `O RLY?`/
+/// `IM IN YR LOOP` bodies -- the same shape of recursive walk as `lsp`'s
+/// `collect_inlay_hints`, just collecting positions instead of hints
+fn collect_coverable_lines(block: &Block, lines: &mut BTreeSet<usize>) {
+    for stmt in &block.statements {
+        lines.insert(statement_line(stmt));
+        match stmt {
+            Statement::ORly { ya_rly, no_wai, .. } => {
+                collect_coverable_lines(ya_rly, lines);
+                if let Some(block) = no_wai {
+                    collect_coverable_lines(block, lines);
+                }
+            }
+            Statement::Loop { body, .. } => collect_coverable_lines(body, lines),
+            _ => {}
+        }
+    }
+}
+
+fn statement_line(stmt: &Statement) -> usize {
+    match stmt {
+        Statement::Declaration { pos, .. }
+        | Statement::Assignment { pos, .. }
+        | Statement::Visible { pos, .. }
+        | Statement::ORly { pos, .. }
+        | Statement::Loop { pos, .. }
+        | Statement::Gtfo { pos, .. }
+        | Statement::Gimmeh { pos, .. }
+        | Statement::Expr { pos, .. } => pos.line,
+    }
+}
+
+/// a runtime lolcode value; `Noob` is both the type of an uninitialized
+/// declaration and the value every cast falls back to. `pub(crate)` so
+/// `eval.rs` can fold a bare expression and report the resulting type
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Value {
+    Noob,
+    Troof(bool),
+    Numbr(i64),
+    Numbar(f64),
+    Yarn(String),
+}
+
+impl Value {
+    /// the lolcode type name of this value's own variant -- unlike
+    /// `lsp::infer_type`, which guesses a static type from an
+    /// unevaluated expression, this is exact because the expression has
+    /// already been folded down to a concrete value
+    pub(crate) fn type_name(&self) -> &'static str {
+        match self {
+            Value::Noob => "NOOB",
+            Value::Troof(_) => "TROOF",
+            Value::Numbr(_) => "NUMBR",
+            Value::Numbar(_) => "NUMBAR",
+            Value::Yarn(_) => "YARN",
+        }
+    }
+
+    fn truthy(&self) -> bool {
+        match self {
+            Value::Noob => false,
+            Value::Troof(b) => *b,
+            Value::Numbr(n) => *n != 0,
+            Value::Numbar(n) => *n != 0.0,
+            Value::Yarn(s) => !s.is_empty(),
+        }
+    }
+
+    /// there's no `MAEK`/`IS NOW A` cast anywhere in this parser either,
+    /// so a `YARN` used in arithmetic is coerced automatically here
+    /// rather than requiring the explicit cast real lolcode does
+    fn is_numbar(&self) -> bool {
+        match self {
+            Value::Numbar(_) => true,
+            Value::Yarn(s) => s.contains('.') && s.parse::<f64>().is_ok(),
+            _ => false,
+        }
+    }
+
+    fn as_numbar(&self) -> f64 {
+        match self {
+            Value::Noob => 0.0,
+            Value::Troof(b) => f64::from(*b),
+            Value::Numbr(n) => *n as f64,
+            Value::Numbar(n) => *n,
+            Value::Yarn(s) => s.parse().unwrap_or(0.0),
+        }
+    }
+
+    fn as_numbr(&self) -> i64 {
+        match self {
+            Value::Noob => 0,
+            Value::Troof(b) => i64::from(*b),
+            Value::Numbr(n) => *n,
+            Value::Numbar(n) => *n as i64,
+            Value::Yarn(s) => s.parse().unwrap_or(0),
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Noob => write!(f, ""),
+            Value::Troof(true) => write!(f, "WIN"),
+            Value::Troof(false) => write!(f, "FAIL"),
+            Value::Numbr(n) => write!(f, "{n}"),
+            Value::Numbar(n) => write!(f, "{n:.2}"),
+            Value::Yarn(s) => write!(f, "{s}"),
+        }
+    }
+}
+
+/// whether a block finished normally or hit `GTFO`; distinct from the
+/// enclosing loop stopping outright, since `GTFO` inside a nested `O
+/// RLY?` still has to unwind out of that block before it reaches the
+/// loop it actually breaks
+enum Flow {
+    Normal,
+    Break,
+    /// a step/output limit was hit; unwinds every enclosing block and
+    /// loop, unlike `Break` which only escapes the nearest loop
+    Halt,
+}
+
+/// holds every variable this program has declared or assigned, including
+/// the implicit `IT`; lolcode has no lexical scoping this parser
+/// represents (see `symbols`' own doc comment), so one flat map for the
+/// whole run matches the language as this crate understands it
+struct Interpreter {
+    vars: HashMap<String, Value>,
+    limits: Limits,
+    steps: usize,
+    output_bytes: usize,
+    /// set the moment a limit is hit; every subsequent statement is
+    /// skipped rather than executed, so one over-limit check stops the
+    /// whole run instead of needing every call site to notice on its own
+    halted: Option<String>,
+    /// per-line execution counts, only populated when `limits.coverage` is
+    /// set -- a loop body increments the same line every iteration, which
+    /// is exactly the count an lcov consumer expects
+    hits: HashMap<usize, u32>,
+}
+
+impl Interpreter {
+    fn new(limits: Limits) -> Self {
+        Self { vars: HashMap::new(), limits, steps: 0, output_bytes: 0, halted: None, hits: HashMap::new() }
+    }
+
+    fn run(&mut self, program: &Program) {
+        self.exec_block(&program.body);
+    }
+
+    fn exec_block(&mut self, block: &Block) -> Flow {
+        for stmt in &block.statements {
+            if self.halted.is_some() {
+                return Flow::Halt;
+            }
+            self.steps += 1;
+            if let Some(max_steps) = self.limits.max_steps {
+                if self.steps > max_steps {
+                    self.halted = Some(format!("step limit of {max_steps} exceeded"));
+                    return Flow::Halt;
+                }
+            }
+            if self.limits.coverage {
+                *self.hits.entry(statement_line(stmt)).or_insert(0) += 1;
+            }
+            match self.exec_stmt(stmt) {
+                Flow::Normal => {}
+                flow @ (Flow::Break | Flow::Halt) => return flow,
+            }
+        }
+        Flow::Normal
+    }
+
+    fn exec_stmt(&mut self, stmt: &Statement) -> Flow {
+        match stmt {
+            Statement::Declaration { name, value, .. } | Statement::Assignment { name, value, .. } => {
+                let value = value.as_ref().map_or(Value::Noob, |expr| self.eval(expr));
+                self.vars.insert(name.clone(), value);
+            }
+            Statement::Visible { expressions, .. } => {
+                let rendered: Vec<String> = expressions.iter().map(|expr| self.eval(expr).to_string()).collect();
+                self.emit(&rendered.join(" "));
+            }
+            Statement::ORly { ya_rly, no_wai, .. } => {
+                let branch_taken = self.vars.get("IT").map(Value::truthy).unwrap_or(false);
+                if branch_taken {
+                    return self.exec_block(ya_rly);
+                } else if let Some(no_wai) = no_wai {
+                    return self.exec_block(no_wai);
+                }
+            }
+            Statement::Loop { body, .. } => loop {
+                match self.exec_block(body) {
+                    Flow::Break => break,
+                    Flow::Halt => return Flow::Halt,
+                    Flow::Normal => {}
+                }
+            },
+            Statement::Gtfo { .. } => return Flow::Break,
+            Statement::Gimmeh { name, .. } => {
+                let input = if self.limits.no_io {
+                    String::new()
+                } else {
+                    let mut line = String::new();
+                    let _ = std::io::stdin().lock().read_line(&mut line);
+                    line.trim_end_matches(['\n', '\r']).to_string()
+                };
+                self.vars.insert(name.clone(), Value::Yarn(input));
+            }
+            Statement::Expr { expression, .. } => {
+                let value = self.eval(expression);
+                self.vars.insert("IT".to_string(), value);
+            }
+        }
+        Flow::Normal
+    }
+
+    /// prints `line` to stdout unless doing so would cross `max_output`,
+    /// in which case the run halts instead -- the same "check before
+    /// acting" shape as the step limit above
+    fn emit(&mut self, line: &str) {
+        if let Some(max_output) = self.limits.max_output {
+            if self.output_bytes + line.len() + 1 > max_output {
+                self.halted = Some(format!("output limit of {max_output} bytes exceeded"));
+                return;
+            }
+        }
+        self.output_bytes += line.len() + 1;
+        println!("{line}");
+    }
+
+    fn eval(&self, expr: &Expression) -> Value {
+        eval_expression(expr, &self.vars)
+    }
+}
+
+/// evaluates `expr` against `vars`, looking up any `Identifier` there and
+/// falling back to `Noob` for one that isn't bound -- a free function
+/// (rather than an `Interpreter` method) so `lol-lint eval` can fold a
+/// bare expression without needing a whole running program around it
+pub(crate) fn eval_expression(expr: &Expression, vars: &HashMap<String, Value>) -> Value {
+    match expr {
+        Expression::Number(n, _) if n.contains('.') => Value::Numbar(n.parse().unwrap_or(0.0)),
+        Expression::Number(n, _) => Value::Numbr(n.parse().unwrap_or(0)),
+        Expression::String(s, _) => Value::Yarn(s.clone()),
+        Expression::Identifier(name, _) => vars.get(name).cloned().unwrap_or(Value::Noob),
+        Expression::Sum { left, right, .. } => numeric_op(left, right, vars, i64::wrapping_add, |a, b| a + b),
+        Expression::Diff { left, right, .. } => numeric_op(left, right, vars, i64::wrapping_sub, |a, b| a - b),
+        Expression::Produkt { left, right, .. } => numeric_op(left, right, vars, i64::wrapping_mul, |a, b| a * b),
+        Expression::Quoshunt { left, right, .. } => numeric_op(
+            left,
+            right,
+            vars,
+            |a, b| if b == 0 { 0 } else { a / b },
+            |a, b| if b == 0.0 { 0.0 } else { a / b },
+        ),
+        Expression::Mod { left, right, .. } => numeric_op(
+            left,
+            right,
+            vars,
+            |a, b| if b == 0 { 0 } else { a % b },
+            |a, b| if b == 0.0 { 0.0 } else { a % b },
+        ),
+        Expression::BothSaem { left, right, .. } => Value::Troof(values_equal(left, right, vars)),
+        Expression::Diffrint { left, right, .. } => Value::Troof(!values_equal(left, right, vars)),
+    }
+}
+
+/// evaluates `left`/`right` and applies `op_i`/`op_f`, promoting to a
+/// float operation if either side is a `NUMBAR`, same as the linter's
+/// own `is_numbar_expr` promotion rule
+fn numeric_op(
+    left: &Expression,
+    right: &Expression,
+    vars: &HashMap<String, Value>,
+    op_i: impl Fn(i64, i64) -> i64,
+    op_f: impl Fn(f64, f64) -> f64,
+) -> Value {
+    let left = eval_expression(left, vars);
+    let right = eval_expression(right, vars);
+    if left.is_numbar() || right.is_numbar() {
+        Value::Numbar(op_f(left.as_numbar(), right.as_numbar()))
+    } else {
+        Value::Numbr(op_i(left.as_numbr(), right.as_numbr()))
+    }
+}
+
+/// numeric equality if either side looks numeric, otherwise a plain
+/// string comparison of both sides' rendered `YARN` form
+fn values_equal(left: &Expression, right: &Expression, vars: &HashMap<String, Value>) -> bool {
+    let left = eval_expression(left, vars);
+    let right = eval_expression(right, vars);
+    if left.is_numbar() || right.is_numbar() {
+        (left.as_numbar() - right.as_numbar()).abs() < f64::EPSILON
+    } else if matches!(left, Value::Numbr(_)) && matches!(right, Value::Numbr(_)) {
+        left.as_numbr() == right.as_numbr()
+    } else {
+        left.to_string() == right.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(source: &str) -> Program {
+        let tokens = Lexer::new(source.to_string()).tokenize();
+        Parser::new(tokens).parse_program()
+    }
+
+    #[test]
+    fn produkt_wraps_at_runtime_instead_of_panicking() {
+        // unlike the linter's compile-time fold_numbr, which gives up
+        // rather than reporting a bogus value for an i64 overflow, a
+        // running program still has to produce *some* NUMBR -- this
+        // documents that the interpreter wraps via wrapping_mul
+        let vars = HashMap::new();
+        let expr = Expression::Produkt {
+            left: Box::new(Expression::Number(i64::MAX.to_string(), zero_pos())),
+            right: Box::new(Expression::Number("2".to_string(), zero_pos())),
+            pos: zero_pos(),
+        };
+        assert_eq!(eval_expression(&expr, &vars), Value::Numbr(i64::MAX.wrapping_mul(2)));
+    }
+
+    #[test]
+    fn quoshunt_by_zero_yields_zero_instead_of_panicking() {
+        let vars = HashMap::new();
+        let expr = Expression::Quoshunt {
+            left: Box::new(Expression::Number("5".to_string(), zero_pos())),
+            right: Box::new(Expression::Number("0".to_string(), zero_pos())),
+            pos: zero_pos(),
+        };
+        assert_eq!(eval_expression(&expr, &vars), Value::Numbr(0));
+    }
+
+    #[test]
+    fn a_declared_but_unassigned_variable_reads_as_noob() {
+        let program = parse("HAI 1.2\nI HAS A x\nKTHXBYE\n");
+        let mut interpreter = Interpreter::new(Limits { max_steps: None, max_output: None, no_io: true, coverage: false });
+        interpreter.run(&program);
+        assert_eq!(interpreter.vars.get("x"), Some(&Value::Noob));
+    }
+
+    #[test]
+    fn step_limit_halts_an_infinite_loop() {
+        let program = parse("HAI 1.2\nIM IN YR LOOP\n    VISIBLE \"hi\"\nIM OUTTA YR LOOP\nKTHXBYE\n");
+        let mut interpreter =
+            Interpreter::new(Limits { max_steps: Some(5), max_output: None, no_io: true, coverage: false });
+        interpreter.run(&program);
+        assert!(interpreter.halted.is_some());
+    }
+
+    #[test]
+    fn collect_coverable_lines_includes_nested_o_rly_and_loop_bodies() {
+        let program = parse(
+            "HAI 1.2\nBOTH SAEM 1 AN 1\nO RLY?\n    YA RLY\n        VISIBLE \"y\"\n    NO WAI\n        VISIBLE \"n\"\nOIC\nKTHXBYE\n",
+        );
+        let mut lines = BTreeSet::new();
+        collect_coverable_lines(&program.body, &mut lines);
+        assert!(lines.contains(&5)); // VISIBLE "y" inside YA RLY
+        assert!(lines.contains(&7)); // VISIBLE "n" inside NO WAI
+    }
+
+    fn zero_pos() -> lol_lint::ast::Position {
+        lol_lint::ast::Position { line: 1, column: 1, start_byte: 0, end_byte: 0 }
+    }
+}
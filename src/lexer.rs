@@ -9,6 +9,10 @@ pub struct Lexer {
     pub position: usize,
     pub line: usize,
     pub column: usize,
+    /// byte offset into `source` of the next character `peek`/`advance`
+    /// will read; tracked alongside the char-indexed `position` since
+    /// tokens report their span in bytes, not chars
+    pub byte: usize,
 }
 
 impl Lexer {
@@ -19,6 +23,7 @@ impl Lexer {
             position: 0,
             line: 1,
             column: 1,
+            byte: 0,
         }
     }
 
@@ -29,10 +34,11 @@ impl Lexer {
         while let Some(ch) = self.peek() {
             match ch {
                 '\n' => {
+                    let start_byte = self.byte;
                     self.advance();
                     let line = self.line;
                     let column = self.column;
-                    tokens.push(Token::new(TokenKind::Newline, line, column));
+                    tokens.push(Token::new(TokenKind::Newline, line, column, start_byte, self.byte));
                 }
                 '"' => {
                     tokens.push(self.read_string());
@@ -61,7 +67,7 @@ impl Lexer {
         self.source.chars().nth(self.position)
     }
 
-    /// advances position by one character, updating line and column tracking
+    /// advances position by one character, updating line, column, and byte tracking
     fn advance(&mut self) {
         if let Some(ch) = self.peek() {
             if ch == '\n' {
@@ -70,6 +76,7 @@ impl Lexer {
             } else {
                 self.column += 1;
             }
+            self.byte += ch.len_utf8();
         }
         self.position += 1;
     }
@@ -78,6 +85,7 @@ impl Lexer {
     fn read_string(&mut self) -> Token {
         let line = self.line;
         let column = self.column;
+        let start_byte = self.byte;
         let mut result = String::new();
         self.advance(); // skip opening quote
 
@@ -90,13 +98,14 @@ impl Lexer {
             self.advance();
         }
 
-        Token::new(TokenKind::StringLiteral(result), line, column)
+        Token::new(TokenKind::StringLiteral(result), line, column, start_byte, self.byte)
     }
 
     /// reads a number literal (integer or float)
     fn read_number(&mut self) -> Token {
         let line = self.line;
         let column = self.column;
+        let start_byte = self.byte;
         let mut result = String::new();
 
         while let Some(ch) = self.peek() {
@@ -108,20 +117,21 @@ impl Lexer {
             }
         }
 
-        Token::new(TokenKind::Number(result), line, column)
+        Token::new(TokenKind::Number(result), line, column, start_byte, self.byte)
     }
 
     /// reads a word (keyword or identifier), checking for comments first
     fn read_word(&mut self) -> Token {
         let line = self.line;
         let column = self.column;
+        let start_byte = self.byte;
 
         // check for btw single-line comment before reading word
         if self.peek_word_matches("BTW") {
             for _ in 0..3 {
                 self.advance();
             }
-            return self.read_comment(line, column);
+            return self.read_comment(line, column, start_byte);
         }
 
         // check for obtw multiline comment before reading word
@@ -129,7 +139,7 @@ impl Lexer {
             for _ in 0..4 {
                 self.advance();
             }
-            return self.read_multiline_comment(line, column);
+            return self.read_multiline_comment(line, column, start_byte);
         }
 
         let mut result = String::new();
@@ -150,7 +160,7 @@ impl Lexer {
             TokenKind::Identifier(result)
         };
 
-        Token::new(kind, line, column)
+        Token::new(kind, line, column, start_byte, self.byte)
     }
 
     /// checks if the next characters match a specific word (case-insensitive)
@@ -175,7 +185,7 @@ impl Lexer {
     }
 
     /// reads a single-line comment (btw) until end of line
-    fn read_comment(&mut self, line: usize, column: usize) -> Token {
+    fn read_comment(&mut self, line: usize, column: usize, start_byte: usize) -> Token {
         let mut result = String::new();
 
         while let Some(ch) = self.peek() {
@@ -186,11 +196,11 @@ impl Lexer {
             self.advance();
         }
 
-        Token::new(TokenKind::Comment(result), line, column)
+        Token::new(TokenKind::Comment(result), line, column, start_byte, self.byte)
     }
 
     /// reads a multiline comment (obtw...tldr) with proper position tracking
-    fn read_multiline_comment(&mut self, line: usize, column: usize) -> Token {
+    fn read_multiline_comment(&mut self, line: usize, column: usize, start_byte: usize) -> Token {
         let mut content = String::new();
 
         loop {
@@ -212,6 +222,7 @@ impl Lexer {
                         } else {
                             self.column += 1;
                         }
+                        self.byte += ch.len_utf8();
                         self.position += 1;
                     }
                 }
@@ -227,11 +238,12 @@ impl Lexer {
                 } else {
                     self.column += 1;
                 }
+                self.byte += ch.len_utf8();
                 self.position += 1;
             }
         }
 
-        Token::new(TokenKind::Comment(content), line, column)
+        Token::new(TokenKind::Comment(content), line, column, start_byte, self.byte)
     }
 
     /// peeks ahead to read the next word in uppercase without advancing
@@ -0,0 +1,132 @@
+// lol_lint: the lexer, parser, and linter behind the `lol-lint` binary,
+// exposed as a library so other rust tools, tests, and bindings can lint
+// a string in-process instead of spawning `lol-lint` and scraping its
+// output.
+
+pub mod ast;
+pub mod cfg;
+pub mod codemod;
+pub mod config;
+pub mod dataflow;
+pub mod diagnostic;
+pub mod fix;
+pub mod lexer;
+pub mod linter;
+pub mod parser;
+pub mod query;
+pub mod ranges;
+pub mod rules;
+pub mod symbols;
+pub mod types;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+use config::LintConfig;
+use diagnostic::Diagnostic;
+use lexer::Lexer;
+use linter::Linter;
+use parser::Parser;
+use serde::Serialize;
+
+/// outcome of linting a string of lolcode source, independent of any
+/// particular output format
+#[derive(Debug, Clone, Serialize)]
+pub struct LintResult {
+    pub errors: Vec<Diagnostic>,
+    pub warnings: Vec<Diagnostic>,
+    /// always `false` from `lint_source`: the parser recovers from syntax
+    /// errors rather than failing outright, so it always produces a usable
+    /// (if partial) program. kept for parity with the binary's `FileResult`,
+    /// which still needs it for input the parser never sees, like a file
+    /// that couldn't be read
+    pub fatal: bool,
+}
+
+/// runs the full lex/parse/lint pipeline over `source` and returns every
+/// diagnostic it produces. this is the same pipeline the `lol-lint`
+/// binary drives per file; it omits binary-only concerns like `--debug`
+/// token/ast dumps, `--stats`, and on-disk caching, which need direct
+/// access to the intermediate tokens/ast the binary already has anyway
+pub fn lint_source(source: &str, config: &LintConfig) -> LintResult {
+    let mut lexer = Lexer::new(source.to_string());
+    let tokens = lexer.tokenize();
+
+    // checked on the raw token stream, before parsing, so a miscased
+    // keyword that would otherwise make the parser fail is still reported
+    let casing_warnings: Vec<Diagnostic> = Linter::check_keyword_casing(&tokens)
+        .iter()
+        .map(|issue| issue.diagnostic())
+        .collect();
+
+    // the parser never panics: a malformed statement, or even a missing
+    // `HAI`/`KTHXBYE`, is recorded in `parser.errors` and parsing carries on
+    // best-effort rather than aborting, so `program` is always usable
+    let mut parser = Parser::new(tokens.clone());
+    let program = parser.parse_program();
+
+    // the parser recovers from a malformed statement by skipping it and
+    // continuing, rather than aborting the whole parse; any such statements
+    // are recorded here as errors instead of being silently dropped
+    let parse_errors = parser.errors;
+
+    let mut linter = Linter::lint_with_config(&program, config);
+    linter.warnings.extend(casing_warnings);
+
+    if config.check_whitespace {
+        linter.warnings.extend(Linter::check_whitespace_style(source));
+    }
+    if let Some(warning) = Linter::check_comment_density(
+        &tokens,
+        count_lines_of_code(source),
+        config.min_comment_density,
+    ) {
+        linter.warnings.push(warning);
+    }
+
+    // resolve `BTW lol-lint-disable-next-line` suppression comments last,
+    // so they can act on warnings gathered from every check above
+    linter.warnings = Linter::apply_suppressions(source, linter.warnings);
+
+    LintResult {
+        errors: parse_errors.into_iter().chain(linter.errors).collect(),
+        warnings: linter.warnings,
+        fatal: false,
+    }
+}
+
+/// counts non-empty, non-comment lines in the source; shared by
+/// `lint_source`'s comment-density check and the binary's `--stats`
+pub fn count_lines_of_code(source: &str) -> usize {
+    source
+        .lines()
+        .filter(|line| {
+            let trimmed = line.trim();
+            !trimmed.is_empty() && !trimmed.starts_with("BTW") && !trimmed.starts_with("OBTW")
+        })
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lint_source_reports_duplicate_declaration() {
+        let source = "HAI 1.2\nI HAS A x ITZ 1\nI HAS A x ITZ 2\nKTHXBYE\n";
+        let result = lint_source(source, &LintConfig::default());
+        assert!(!result.fatal);
+        assert!(result.errors.iter().any(|d| d.code == Some("LL001")));
+    }
+
+    #[test]
+    fn lint_source_recovers_from_parse_errors_instead_of_failing() {
+        let source = "HAI 1.2\nI HAS A\nKTHXBYE\n";
+        let result = lint_source(source, &LintConfig::default());
+        assert!(!result.fatal);
+        assert!(!result.errors.is_empty());
+    }
+}
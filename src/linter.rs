@@ -1,37 +1,170 @@
 // linter: semantic analysis and code quality checks
 // performs variable tracking, detects errors and warnings
 
-use crate::ast::{Block, Expression, Program, Statement};
-use std::collections::HashSet;
+use crate::ast::{Block, Expression, Position, Program, Statement};
+use crate::config::LintConfig;
+use crate::diagnostic::{Diagnostic, Span};
+use std::collections::{HashMap, HashSet};
 
 /// linter state tracking errors, warnings, and variable usage
 #[derive(Debug)]
 pub struct Linter {
-    pub errors: Vec<String>,
-    pub warnings: Vec<String>,
+    pub errors: Vec<Diagnostic>,
+    pub warnings: Vec<Diagnostic>,
+    /// (message, position) for each declared-but-never-used variable that
+    /// is safe to autofix by deleting its whole declaration line, i.e.
+    /// never touched by GIMMEH (see `check_unused_variables`)
+    pub unused_declarations: Vec<(String, Position)>,
+    /// one entry per "declared twice" error whose second `I HAS A ...`
+    /// has an initializer, and so is safe to autofix into an assignment
+    /// (see `check_statement`'s `Statement::Declaration` arm)
+    pub duplicate_declarations: Vec<DuplicateDeclarationFix>,
+    /// one entry per O RLY? block missing a NO WAI branch, safe to autofix
+    /// by inserting an empty one just before OIC (see `Statement::ORly`)
+    pub missing_no_wai: Vec<MissingNoWaiFix>,
     declared_vars: HashSet<String>,
+    declared_positions: HashMap<String, Position>,
     used_vars: HashSet<String>,
+    numbar_vars: HashSet<String>,
+    initialized_vars: HashSet<String>,
+    tainted_vars: HashSet<String>,
+    /// known constant value of each variable, up to this point in a single
+    /// linear pass over the program; a variable drops out the moment it's
+    /// assigned something other than a literal, or read via GIMMEH, since
+    /// its value is no longer known statically. this is intentionally
+    /// flow-insensitive across branches -- a value set inside one O RLY?
+    /// branch is still considered known after the block, same as the rest
+    /// of this linter's single-pass tracking (`numbar_vars`, `tainted_vars`)
+    constants: HashMap<String, ConstValue>,
+    config: LintConfig,
+}
+
+/// a variable's statically-known value, used to fold constants that flow
+/// through a variable before an arithmetic or comparison check runs
+#[derive(Debug, Clone, PartialEq)]
+enum ConstValue {
+    Numbr(i64),
+    Numbar(f64),
+    Str(String),
+}
+
+/// a keyword typed in the wrong case (e.g. `visible` instead of
+/// `VISIBLE`), which the lexer tokenizes as a plain identifier since
+/// keyword matching is exact-case; carries enough to both format the
+/// warning and build a `--fix` suggestion for it
+pub struct KeywordCasingIssue {
+    pub written: String,
+    pub correct: String,
+    pub pos: Position,
+}
+
+impl KeywordCasingIssue {
+    pub fn message(&self) -> String {
+        format!(
+            "warning: '{}' should be written as the keyword '{}' (line {}, column {})",
+            self.written, self.correct, self.pos.line, self.pos.column
+        )
+    }
+
+    pub fn diagnostic(&self) -> Diagnostic {
+        Diagnostic::warning(
+            Some("LL022"),
+            self.message(),
+            Some(Span::from_position(&self.pos)),
+        )
+    }
+}
+
+/// a "declared twice" error whose second declaration has an initializer,
+/// and so can be rewritten from `I HAS A x ITZ v` into the plain
+/// assignment `x R v` it almost always meant to be
+#[derive(Debug)]
+pub struct DuplicateDeclarationFix {
+    pub message: String,
+    pub name: String,
+    /// position of the declaration's leading `I` keyword
+    pub decl_pos: Position,
+    /// position of the initializer expression's leading token, i.e. where
+    /// `v` starts in `ITZ v`
+    pub value_pos: Position,
+}
+
+/// an O RLY? block with no NO WAI branch, and so can have an empty one
+/// inserted right before its closing OIC
+#[derive(Debug)]
+pub struct MissingNoWaiFix {
+    pub message: String,
+    /// position of the closing OIC, where the new branch is inserted
+    pub oic_pos: Position,
+    /// column (0-based) to indent the inserted NO WAI and its placeholder
+    /// comment at, matching the O RLY? block's own indentation
+    pub indent: usize,
 }
 
 impl Linter {
-    /// performs semantic analysis on the entire program
-    pub fn lint(program: &Program) -> Self {
+    /// performs semantic analysis on the entire program with the given configuration
+    pub fn lint_with_config(program: &Program, config: &LintConfig) -> Self {
         let mut linter = Linter {
             errors: vec![],
             warnings: vec![],
+            unused_declarations: vec![],
+            duplicate_declarations: vec![],
+            missing_no_wai: vec![],
             declared_vars: HashSet::new(),
+            declared_positions: HashMap::new(),
             used_vars: HashSet::new(),
+            numbar_vars: HashSet::new(),
+            initialized_vars: HashSet::new(),
+            tainted_vars: HashSet::new(),
+            constants: HashMap::new(),
+            config: config.clone(),
         };
 
         linter.check_block(&program.body);
         linter.check_unused_variables();
+        linter.sort_diagnostics();
 
         linter
     }
 
+    /// sorts errors and warnings by source position so runs are
+    /// reproducible regardless of HashSet iteration order (e.g. the
+    /// unused-variable pass iterates `declared_vars`); diagnostics with no
+    /// span sort last, in their original relative order
+    fn sort_diagnostics(&mut self) {
+        Self::sort_by_position(&mut self.errors);
+        Self::sort_by_position(&mut self.warnings);
+    }
+
+    fn sort_by_position(diagnostics: &mut [Diagnostic]) {
+        diagnostics.sort_by_key(|d| match d.span {
+            Some(span) => (span.line, span.column.unwrap_or(usize::MAX)),
+            None => (usize::MAX, usize::MAX),
+        });
+    }
+
     /// recursively checks all statements in a block
     fn check_block(&mut self, block: &Block) {
+        let mut seen_non_declaration = false;
+
         for stmt in &block.statements {
+            if self.config.declarations_at_top {
+                if let Statement::Declaration { pos, .. } = stmt {
+                    if seen_non_declaration {
+                        self.warnings.push(Diagnostic::warning(
+                            Some("LL019"),
+                            format!(
+                                "warning: declaration appears after other statements in this block (line {}, column {})",
+                                pos.line, pos.column
+                            ),
+                            Some(Span::from_position(pos)),
+                        ));
+                    }
+                } else {
+                    seen_non_declaration = true;
+                }
+            }
+
             self.check_statement(stmt);
         }
     }
@@ -42,26 +175,65 @@ impl Linter {
             Statement::Declaration { name, value, pos } => {
                 // detect double declarations
                 if self.declared_vars.contains(name) {
-                    self.errors.push(format!(
+                    let message = format!(
                         "error: variable '{}' declared twice (line {}, column {})",
                         name, pos.line, pos.column
+                    );
+                    // the intent behind a second `I HAS A x ITZ v` is
+                    // almost always a plain assignment `x R v`; only
+                    // offer that fix when there's a value to assign, since
+                    // a bare `I HAS A x` has nothing to rewrite it as
+                    if let Some(expr) = value {
+                        self.duplicate_declarations.push(DuplicateDeclarationFix {
+                            message: message.clone(),
+                            name: name.clone(),
+                            decl_pos: pos.clone(),
+                            value_pos: expr.position().clone(),
+                        });
+                    }
+                    self.errors.push(Diagnostic::error(
+                        Some("LL001"),
+                        message,
+                        Some(Span::from_position(pos)),
                     ));
                 } else {
                     self.declared_vars.insert(name.clone());
+                    self.declared_positions.insert(name.clone(), pos.clone());
                 }
 
+                self.check_identifier_length(name, pos);
+                self.check_keyword_like_identifier(name, pos);
+
                 // check initialization expression if present
                 if let Some(expr) = value {
                     self.check_expression(expr);
+                    if self.is_numbar_expr(expr) {
+                        self.numbar_vars.insert(name.clone());
+                    }
+                    self.initialized_vars.insert(name.clone());
+                    self.update_constant(name, expr);
                 }
             }
 
             Statement::Assignment { name, value, pos } => {
-                // detect assignment to undeclared variables
-                if !self.declared_vars.contains(name) {
-                    self.errors.push(format!(
-                        "error: assignment to undeclared variable '{}' (line {}, column {})",
-                        name, pos.line, pos.column
+                // reserved names (IT, WIN, FAIL) can never be assignment targets
+                if crate::types::Token::is_reserved_name(name) {
+                    self.errors.push(Diagnostic::error(
+                        Some("LL003"),
+                        format!(
+                            "error: cannot assign to reserved name '{}' (line {}, column {})",
+                            name, pos.line, pos.column
+                        ),
+                        Some(Span::from_position(pos)),
+                    ));
+                } else if !self.declared_vars.contains(name) {
+                    self.errors.push(Diagnostic::error(
+                        Some("LL002"),
+                        format!(
+                            "error: assignment to undeclared variable '{}' (line {}, column {})",
+                            name, pos.line, pos.column
+                        ),
+                        Some(Span::from_position(pos)),
                     ));
                 } else {
                     self.used_vars.insert(name.clone());
@@ -70,7 +242,33 @@ impl Linter {
                 // check assignment expression if present
                 if let Some(expr) = value {
                     self.check_expression(expr);
+                    if self.is_numbar_expr(expr) {
+                        self.numbar_vars.insert(name.clone());
+                    }
+                    self.initialized_vars.insert(name.clone());
+                    // a fresh assignment overwrites any tainted raw input
+                    self.tainted_vars.remove(name);
+                    self.update_constant(name, expr);
+                }
+            }
+
+            Statement::Gimmeh { name, pos } => {
+                if !self.declared_vars.contains(name) {
+                    self.errors.push(Diagnostic::error(
+                        Some("LL002"),
+                        format!(
+                            "error: GIMMEH into undeclared variable '{}' (line {}, column {})",
+                            name, pos.line, pos.column
+                        ),
+                        Some(Span::from_position(pos)),
+                    ));
                 }
+
+                self.initialized_vars.insert(name.clone());
+                self.tainted_vars.insert(name.clone());
+                // GIMMEH overwrites whatever value the constant tracker
+                // thought this variable held with runtime input
+                self.constants.remove(name);
             }
 
             Statement::Visible {
@@ -80,6 +278,7 @@ impl Linter {
                 // validate all expressions in output statement
                 for expr in expressions {
                     self.check_expression(expr);
+                    self.check_noob_usage(expr);
                 }
             }
 
@@ -87,12 +286,17 @@ impl Linter {
                 ya_rly,
                 no_wai,
                 pos,
+                oic_pos,
             } => {
                 // warn about empty if branches
                 if ya_rly.statements.is_empty() {
-                    self.warnings.push(format!(
-                        "warning: YA RLY block is empty (line {}, column {})",
-                        pos.line, pos.column
+                    self.warnings.push(Diagnostic::warning(
+                        Some("LL010"),
+                        format!(
+                            "warning: YA RLY block is empty (line {}, column {})",
+                            pos.line, pos.column
+                        ),
+                        Some(Span::from_position(pos)),
                     ));
                 }
 
@@ -102,9 +306,19 @@ impl Linter {
                 if let Some(no_block) = no_wai {
                     self.check_block(no_block);
                 } else {
-                    self.warnings.push(format!(
+                    let message = format!(
                         "warning: O RLY? without NO WAI branch (line {}, column {})",
                         pos.line, pos.column
+                    );
+                    self.missing_no_wai.push(MissingNoWaiFix {
+                        message: message.clone(),
+                        oic_pos: oic_pos.clone(),
+                        indent: pos.column - 1 + 4,
+                    });
+                    self.warnings.push(Diagnostic::warning(
+                        Some("LL009"),
+                        message,
+                        Some(Span::from_position(pos)),
                     ));
                 }
             }
@@ -112,13 +326,42 @@ impl Linter {
             Statement::Loop { body, pos } => {
                 // warn about empty loop bodies
                 if body.statements.is_empty() {
-                    self.warnings.push(format!(
-                        "warning: empty loop body (line {}, column {})",
-                        pos.line, pos.column
+                    self.warnings.push(Diagnostic::warning(
+                        Some("LL007"),
+                        format!(
+                            "warning: empty loop body (line {}, column {})",
+                            pos.line, pos.column
+                        ),
+                        Some(Span::from_position(pos)),
+                    ));
+                }
+
+                // warn when the loop unconditionally breaks on its first statement
+                if matches!(body.statements.first(), Some(Statement::Gtfo { .. })) {
+                    self.warnings.push(Diagnostic::warning(
+                        Some("LL008"),
+                        format!(
+                            "warning: loop always exits on the first iteration due to an unconditional GTFO (line {}, column {})",
+                            pos.line, pos.column
+                        ),
+                        Some(Span::from_position(pos)),
                     ));
                 }
 
+                // snapshotted before entering the body, since a counter
+                // stepped inside the loop is no longer a known constant by
+                // the time `check_block` finishes with it
+                let initial = self.numbr_constants();
+
                 self.check_block(body);
+
+                for diagnostic in crate::ranges::check_unreachable_guards(body, &initial) {
+                    self.warnings.push(diagnostic);
+                }
+            }
+
+            Statement::Gtfo { .. } => {
+                // no semantic checks needed; validity of GTFO placement is a parser concern
             }
 
             Statement::Expr { expression, pos: _ } => {
@@ -138,9 +381,13 @@ impl Linter {
             Expression::Identifier(name, pos) => {
                 // detect use of undeclared variables
                 if !self.declared_vars.contains(name) {
-                    self.errors.push(format!(
-                        "error: use of undeclared variable '{}' (line {}, column {})",
-                        name, pos.line, pos.column
+                    self.errors.push(Diagnostic::error(
+                        Some("LL002"),
+                        format!(
+                            "error: use of undeclared variable '{}' (line {}, column {})",
+                            name, pos.line, pos.column
+                        ),
+                        Some(Span::from_position(pos)),
                     ));
                 } else {
                     self.used_vars.insert(name.clone());
@@ -151,56 +398,252 @@ impl Linter {
                 // literals are always valid
             }
 
-            // recursively check binary operations
-            Expression::Sum { left, right, .. }
-            | Expression::Diff { left, right, .. }
-            | Expression::Produkt { left, right, .. }
-            | Expression::Quoshunt { left, right, .. }
-            | Expression::Mod { left, right, .. }
-            | Expression::BothSaem { left, right, .. }
-            | Expression::Diffrint { left, right, .. } => {
+            // recursively check arithmetic operations, plus constant overflow
+            Expression::Sum { left, right, pos }
+            | Expression::Diff { left, right, pos }
+            | Expression::Produkt { left, right, pos }
+            | Expression::Quoshunt { left, right, pos }
+            | Expression::Mod { left, right, pos } => {
+                self.check_expression(left);
+                self.check_expression(right);
+
+                if let Some(warning) = self.check_numeric_overflow(expr, pos) {
+                    self.warnings.push(warning);
+                }
+                if let Some(warning) = self.check_division_by_zero(expr, pos) {
+                    self.warnings.push(warning);
+                }
+
+                self.check_tainted_arithmetic(left, pos);
+                self.check_tainted_arithmetic(right, pos);
+            }
+
+            Expression::Diffrint { left, right, .. } => {
+                self.check_expression(left);
+                self.check_expression(right);
+            }
+
+            Expression::BothSaem { left, right, pos } => {
                 self.check_expression(left);
                 self.check_expression(right);
+
+                // float equality is unreliable due to precision loss
+                if self.is_numbar_expr(left) || self.is_numbar_expr(right) {
+                    self.warnings.push(Diagnostic::warning(
+                        Some("LL005"),
+                        format!(
+                            "warning: BOTH SAEM compares a NUMBAR and may be unreliable due to floating point precision; compare DIFF OF against a small tolerance instead (line {}, column {})",
+                            pos.line, pos.column
+                        ),
+                        Some(Span::from_position(pos)),
+                    ));
+                }
+            }
+        }
+    }
+
+    /// folds a NUMBR-valued arithmetic expression to its value, consulting
+    /// constant propagation (`self.constants`) for identifiers, and
+    /// returning `None` if it involves anything that isn't a known NUMBR
+    /// value (strings, NUMBAR literals, an unresolved variable, division
+    /// by zero), or if a nested constant sub-expression overflows `i64` --
+    /// unlike `interpreter.rs`'s `numeric_op`, which wraps at runtime with
+    /// `wrapping_*` since a running program has to produce *some* value,
+    /// this is a compile-time fold: an operation that can't even be
+    /// represented in `i64` has nothing valid to report `check_numeric_overflow`
+    /// against, so it's treated the same as any other unfoldable expression
+    fn fold_numbr(&self, expr: &Expression) -> Option<i64> {
+        match expr {
+            Expression::Number(n, _) if !n.contains('.') => n.parse::<i64>().ok(),
+            Expression::Identifier(name, _) => match self.constants.get(name) {
+                Some(ConstValue::Numbr(v)) => Some(*v),
+                _ => None,
+            },
+            Expression::Sum { left, right, .. } => {
+                self.fold_numbr(left)?.checked_add(self.fold_numbr(right)?)
+            }
+            Expression::Diff { left, right, .. } => {
+                self.fold_numbr(left)?.checked_sub(self.fold_numbr(right)?)
+            }
+            Expression::Produkt { left, right, .. } => {
+                self.fold_numbr(left)?.checked_mul(self.fold_numbr(right)?)
+            }
+            Expression::Quoshunt { left, right, .. } => {
+                let divisor = self.fold_numbr(right)?;
+                if divisor == 0 {
+                    None
+                } else {
+                    self.fold_numbr(left)?.checked_div(divisor)
+                }
+            }
+            Expression::Mod { left, right, .. } => {
+                let divisor = self.fold_numbr(right)?;
+                if divisor == 0 {
+                    None
+                } else {
+                    self.fold_numbr(left)?.checked_rem(divisor)
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// warns when a folded constant arithmetic expression falls outside
+    /// lolcode's 32-bit NUMBR range and would wrap or lose precision
+    fn check_numeric_overflow(&self, expr: &Expression, pos: &crate::ast::Position) -> Option<Diagnostic> {
+        let value = self.fold_numbr(expr)?;
+
+        if value < i32::MIN as i64 || value > i32::MAX as i64 {
+            Some(Diagnostic::warning(
+                Some("LL006"),
+                format!(
+                    "warning: constant expression folds to {}, which overflows lolcode's NUMBR range and would wrap to {} at runtime (line {}, column {})",
+                    value, value as i32, pos.line, pos.column
+                ),
+                Some(Span::from_position(pos)),
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// warns when a QUOSHUNT OF or MOD OF's divisor folds to a known
+    /// constant zero, including when that zero flows in through a variable
+    /// constant propagation has proven holds it (e.g. `I HAS A d ITZ 0`)
+    fn check_division_by_zero(&self, expr: &Expression, pos: &crate::ast::Position) -> Option<Diagnostic> {
+        let (right, op) = match expr {
+            Expression::Quoshunt { right, .. } => (right, "QUOSHUNT OF"),
+            Expression::Mod { right, .. } => (right, "MOD OF"),
+            _ => return None,
+        };
+
+        if self.fold_numbr(right) == Some(0) {
+            Some(Diagnostic::warning(
+                Some("LL023"),
+                format!(
+                    "warning: {} divides by a constant zero (line {}, column {})",
+                    op, pos.line, pos.column
+                ),
+                Some(Span::from_position(pos)),
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// records `name`'s value in the constant table when `expr` is a
+    /// literal or another variable already known to be constant, or drops
+    /// it when the value can no longer be tracked statically
+    fn update_constant(&mut self, name: &str, expr: &Expression) {
+        match self.resolve_constant(expr) {
+            Some(value) => {
+                self.constants.insert(name.to_string(), value);
+            }
+            None => {
+                self.constants.remove(name);
+            }
+        }
+    }
+
+    /// snapshot of every variable currently known to hold a constant NUMBR
+    /// value, for passes (like `ranges::check_unreachable_guards`) that
+    /// only reason about integers
+    fn numbr_constants(&self) -> HashMap<String, i64> {
+        self.constants
+            .iter()
+            .filter_map(|(name, value)| match value {
+                ConstValue::Numbr(v) => Some((name.clone(), *v)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// resolves `expr` to a statically-known constant value, either a
+    /// literal directly or a variable constant propagation has already
+    /// proven holds one
+    fn resolve_constant(&self, expr: &Expression) -> Option<ConstValue> {
+        match expr {
+            Expression::Number(n, _) if n.contains('.') => n.parse::<f64>().ok().map(ConstValue::Numbar),
+            Expression::Number(n, _) => n.parse::<i64>().ok().map(ConstValue::Numbr),
+            Expression::String(s, _) => Some(ConstValue::Str(s.clone())),
+            Expression::Identifier(name, _) => self.constants.get(name).cloned(),
+            _ => None,
+        }
+    }
+
+    /// warns when a raw, unconverted GIMMEH input is used as an arithmetic
+    /// operand; lolcode reads GIMMEH input as a YARN, so it must be cast to
+    /// a NUMBR/NUMBAR before arithmetic will behave as expected
+    fn check_tainted_arithmetic(&mut self, operand: &Expression, pos: &crate::ast::Position) {
+        if let Expression::Identifier(name, _) = operand {
+            if self.tainted_vars.contains(name) {
+                self.warnings.push(Diagnostic::warning(
+                    Some("LL013"),
+                    format!(
+                        "warning: '{}' holds raw GIMMEH input (a YARN) and is used in arithmetic without an explicit cast (line {}, column {})",
+                        name, pos.line, pos.column
+                    ),
+                    Some(Span::from_position(pos)),
+                ));
             }
         }
     }
 
+    /// returns true if the expression is known to be (or produce) a NUMBAR
+    fn is_numbar_expr(&self, expr: &Expression) -> bool {
+        match expr {
+            Expression::Number(n, _) => n.contains('.'),
+            Expression::Identifier(name, _) => self.numbar_vars.contains(name),
+            _ => false,
+        }
+    }
+
     /// detects constant expressions that always evaluate to true or false
-    fn check_constant_expression(&self, expr: &Expression) -> Option<String> {
+    fn check_constant_expression(&self, expr: &Expression) -> Option<Diagnostic> {
         match expr {
             Expression::BothSaem { left, right, pos } => {
                 // detect comparisons between identical number literals
                 if let (Expression::Number(n1, _), Expression::Number(n2, _)) =
                     (left.as_ref(), right.as_ref())
                 {
-                    if n1 == n2 {
-                        return Some(format!(
+                    let message = if n1 == n2 {
+                        format!(
                             "warning: BOTH SAEM {} AN {} is always true (line {}, column {})",
                             n1, n2, pos.line, pos.column
-                        ));
+                        )
                     } else {
-                        return Some(format!(
+                        format!(
                             "warning: BOTH SAEM {} AN {} is always false (line {}, column {})",
                             n1, n2, pos.line, pos.column
-                        ));
-                    }
+                        )
+                    };
+                    return Some(Diagnostic::warning(
+                        Some("LL021"),
+                        message,
+                        Some(Span::from_position(pos)),
+                    ));
                 }
 
                 // detect comparisons between identical string literals
                 if let (Expression::String(s1, _), Expression::String(s2, _)) =
                     (left.as_ref(), right.as_ref())
                 {
-                    if s1 == s2 {
-                        return Some(format!(
+                    let message = if s1 == s2 {
+                        format!(
                             "warning: BOTH SAEM \"{}\" AN \"{}\" is always true (line {}, column {})",
                             s1, s2, pos.line, pos.column
-                        ));
+                        )
                     } else {
-                        return Some(format!(
+                        format!(
                             "warning: BOTH SAEM \"{}\" AN \"{}\" is always false (line {}, column {})",
                             s1, s2, pos.line, pos.column
-                        ));
-                    }
+                        )
+                    };
+                    return Some(Diagnostic::warning(
+                        Some("LL021"),
+                        message,
+                        Some(Span::from_position(pos)),
+                    ));
                 }
 
                 // detect comparisons between identical constants (win, fail)
@@ -208,12 +651,31 @@ impl Linter {
                     (left.as_ref(), right.as_ref())
                 {
                     if i1 == i2 && (i1 == "WIN" || i1 == "FAIL") {
-                        return Some(format!(
-                            "warning: BOTH SAEM {} AN {} is always true (line {}, column {})",
-                            i1, i2, pos.line, pos.column
+                        return Some(Diagnostic::warning(
+                            Some("LL021"),
+                            format!(
+                                "warning: BOTH SAEM {} AN {} is always true (line {}, column {})",
+                                i1, i2, pos.line, pos.column
+                            ),
+                            Some(Span::from_position(pos)),
                         ));
                     }
                 }
+
+                // neither side was a literal matching the other directly,
+                // but constant propagation may still know both values --
+                // e.g. one side is a variable ITZ'd from a literal
+                if let (Some(l), Some(r)) = (self.resolve_constant(left), self.resolve_constant(right)) {
+                    let outcome = if l == r { "always true" } else { "always false" };
+                    return Some(Diagnostic::warning(
+                        Some("LL021"),
+                        format!(
+                            "warning: BOTH SAEM compares two compile-time constant values and is {} (line {}, column {})",
+                            outcome, pos.line, pos.column
+                        ),
+                        Some(Span::from_position(pos)),
+                    ));
+                }
             }
 
             Expression::Diffrint { left, right, pos } => {
@@ -221,17 +683,34 @@ impl Linter {
                 if let (Expression::Number(n1, _), Expression::Number(n2, _)) =
                     (left.as_ref(), right.as_ref())
                 {
-                    if n1 == n2 {
-                        return Some(format!(
+                    let message = if n1 == n2 {
+                        format!(
                             "warning: DIFFRINT {} AN {} is always false (line {}, column {})",
                             n1, n2, pos.line, pos.column
-                        ));
+                        )
                     } else {
-                        return Some(format!(
+                        format!(
                             "warning: DIFFRINT {} AN {} is always true (line {}, column {})",
                             n1, n2, pos.line, pos.column
-                        ));
-                    }
+                        )
+                    };
+                    return Some(Diagnostic::warning(
+                        Some("LL021"),
+                        message,
+                        Some(Span::from_position(pos)),
+                    ));
+                }
+
+                if let (Some(l), Some(r)) = (self.resolve_constant(left), self.resolve_constant(right)) {
+                    let outcome = if l == r { "always false" } else { "always true" };
+                    return Some(Diagnostic::warning(
+                        Some("LL021"),
+                        format!(
+                            "warning: DIFFRINT compares two compile-time constant values and is {} (line {}, column {})",
+                            outcome, pos.line, pos.column
+                        ),
+                        Some(Span::from_position(pos)),
+                    ));
                 }
             }
 
@@ -241,20 +720,297 @@ impl Linter {
         None
     }
 
+    /// warns when a declared-but-never-initialized (possibly NOOB) variable
+    /// is printed with VISIBLE; only flags direct identifier references,
+    /// since this linter does not track flow through conditionals
+    fn check_noob_usage(&mut self, expr: &Expression) {
+        if let Expression::Identifier(name, pos) = expr {
+            if self.declared_vars.contains(name) && !self.initialized_vars.contains(name) {
+                self.warnings.push(Diagnostic::warning(
+                    Some("LL011"),
+                    format!(
+                        "warning: '{}' may still be NOOB (declared without ITZ and never assigned) here (line {}, column {})",
+                        name, pos.line, pos.column
+                    ),
+                    Some(Span::from_position(pos)),
+                ));
+            }
+        }
+    }
+
+    /// checks raw source text for trailing whitespace, mixed tab/space
+    /// indentation, and a missing final newline; operates on source text
+    /// directly since these are formatting concerns, not ast concerns
+    pub fn check_whitespace_style(source: &str) -> Vec<Diagnostic> {
+        let mut warnings = Vec::new();
+
+        if !source.is_empty() && !source.ends_with('\n') {
+            warnings.push(Diagnostic::warning(
+                Some("LL017"),
+                "warning: file is missing a final newline".to_string(),
+                None,
+            ));
+        }
+
+        for (i, line) in source.lines().enumerate() {
+            let line_no = i + 1;
+
+            if line.ends_with(' ') || line.ends_with('\t') {
+                warnings.push(Diagnostic::warning(
+                    Some("LL016"),
+                    format!("warning: trailing whitespace (line {})", line_no),
+                    Some(Span::line_only(line_no)),
+                ));
+            }
+
+            let indent: String = line.chars().take_while(|c| *c == ' ' || *c == '\t').collect();
+            if indent.contains(' ') && indent.contains('\t') {
+                warnings.push(Diagnostic::warning(
+                    Some("LL016"),
+                    format!(
+                        "warning: line mixes tabs and spaces in its indentation (line {})",
+                        line_no
+                    ),
+                    Some(Span::line_only(line_no)),
+                ));
+            }
+        }
+
+        warnings
+    }
+
+    /// checks tokens for identifiers that are really a lolcode keyword
+    /// typed with the wrong casing; skips keywords one or two letters long
+    /// (`I`, `A`, `R`, `O`, `AN`, `OF`, `IN`, `NO`, ...) since those are
+    /// common short variable names and would make this check too noisy to
+    /// trust. operates on tokens directly, before parsing, so a miscased
+    /// keyword that would otherwise fail to parse is still reported
+    pub fn check_keyword_casing(tokens: &[crate::types::Token]) -> Vec<KeywordCasingIssue> {
+        tokens
+            .iter()
+            .filter_map(|token| {
+                let crate::types::TokenKind::Identifier(word) = &token.kind else {
+                    return None;
+                };
+                if word.chars().count() < 3 {
+                    return None;
+                }
+                let upper = word.to_uppercase();
+                if !crate::types::Token::is_keyword(&upper) {
+                    return None;
+                }
+                Some(KeywordCasingIssue {
+                    written: word.clone(),
+                    correct: upper,
+                    pos: crate::ast::Position::from_token(token),
+                })
+            })
+            .collect()
+    }
+
+    /// warns when the ratio of comment lines to code lines falls below the
+    /// configured minimum percentage; a disabled (0.0) threshold never warns
+    pub fn check_comment_density(
+        tokens: &[crate::types::Token],
+        lines_of_code: usize,
+        min_percent: f64,
+    ) -> Option<Diagnostic> {
+        if min_percent <= 0.0 || lines_of_code == 0 {
+            return None;
+        }
+
+        let comment_lines = tokens
+            .iter()
+            .filter(|t| matches!(t.kind, crate::types::TokenKind::Comment(_)))
+            .count();
+        let percent = (comment_lines as f64 / lines_of_code as f64) * 100.0;
+
+        if percent < min_percent {
+            Some(Diagnostic::warning(
+                Some("LL018"),
+                format!(
+                    "warning: comment density is {:.1}%, below the configured minimum of {:.1}%",
+                    percent, min_percent
+                ),
+                None,
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// applies `BTW lol-lint-disable-next-line` suppression comments to a
+    /// list of diagnostics, dropping any warning on the suppressed line and
+    /// reporting suppression comments that never matched a warning
+    pub fn apply_suppressions(source: &str, warnings: Vec<Diagnostic>) -> Vec<Diagnostic> {
+        let suppressed_lines: Vec<usize> = source
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| {
+                let trimmed = line.trim();
+                trimmed.starts_with("BTW")
+                    && trimmed.to_uppercase().contains("LOL-LINT-DISABLE-NEXT-LINE")
+            })
+            .map(|(i, _)| i + 2) // the comment suppresses the line right after it
+            .collect();
+
+        let mut used = vec![false; suppressed_lines.len()];
+        let mut kept = Vec::new();
+
+        for warning in warnings {
+            match warning.span.map(|span| span.line) {
+                Some(line) if suppressed_lines.contains(&line) => {
+                    let idx = suppressed_lines.iter().position(|&l| l == line).unwrap();
+                    used[idx] = true;
+                }
+                _ => kept.push(warning),
+            }
+        }
+
+        for (idx, &line) in suppressed_lines.iter().enumerate() {
+            if !used[idx] {
+                kept.push(Diagnostic::warning(
+                    Some("LL020"),
+                    format!("warning: unused lint suppression comment (line {})", line - 1),
+                    Some(Span::line_only(line - 1)),
+                ));
+            }
+        }
+
+        kept
+    }
+
+    /// warns about identifiers outside the configured min/max length bounds
+    fn check_identifier_length(&mut self, name: &str, pos: &crate::ast::Position) {
+        let len = name.chars().count();
+
+        if self.config.min_identifier_length > 0 && len < self.config.min_identifier_length {
+            self.warnings.push(Diagnostic::warning(
+                Some("LL014"),
+                format!(
+                    "warning: identifier '{}' is shorter than the minimum length of {} (line {}, column {})",
+                    name, self.config.min_identifier_length, pos.line, pos.column
+                ),
+                Some(Span::from_position(pos)),
+            ));
+        }
+
+        if self.config.max_identifier_length > 0 && len > self.config.max_identifier_length {
+            self.warnings.push(Diagnostic::warning(
+                Some("LL014"),
+                format!(
+                    "warning: identifier '{}' is longer than the maximum length of {} (line {}, column {})",
+                    name, self.config.max_identifier_length, pos.line, pos.column
+                ),
+                Some(Span::from_position(pos)),
+            ));
+        }
+    }
+
+    /// warns about identifiers that collide with lolcode keywords or
+    /// builtins that this parser does not (yet) reserve as `Keyword` tokens
+    fn check_keyword_like_identifier(&mut self, name: &str, pos: &crate::ast::Position) {
+        if crate::types::Token::is_near_keyword(name) {
+            self.warnings.push(Diagnostic::warning(
+                Some("LL015"),
+                format!(
+                    "warning: identifier '{}' collides with a lolcode keyword and may cause confusing behavior (line {}, column {})",
+                    name, pos.line, pos.column
+                ),
+                Some(Span::from_position(pos)),
+            ));
+        }
+    }
+
     /// warns about variables that are declared but never used
     fn check_unused_variables(&mut self) {
-        for var in &self.declared_vars {
-            if !self.used_vars.contains(var) {
-                self.warnings.push(format!(
-                    "warning: variable '{}' declared but never used",
-                    var
+        // iterate in sorted order rather than raw HashSet order so the
+        // final (position-tied) warnings come out the same on every run
+        let mut declared: Vec<&String> = self.declared_vars.iter().collect();
+        declared.sort();
+
+        for var in declared {
+            if self.used_vars.contains(var) {
+                continue;
+            }
+
+            if self.tainted_vars.contains(var) {
+                // GIMMEH has an observable side effect (reading input), so
+                // deleting the declaration line would silently change the
+                // program's I/O behavior -- not offered as an autofix
+                self.warnings.push(Diagnostic::warning(
+                    Some("LL012"),
+                    format!(
+                        "warning: variable '{}' received GIMMEH input but is never used",
+                        var
+                    ),
+                    None,
                 ));
+            } else {
+                match self.declared_positions.get(var).cloned() {
+                    Some(pos) => {
+                        let message = format!(
+                            "warning: variable '{}' declared but never used (line {}, column {})",
+                            var, pos.line, pos.column
+                        );
+                        self.unused_declarations.push((message.clone(), pos.clone()));
+                        self.warnings.push(Diagnostic::warning(
+                            Some("LL004"),
+                            message,
+                            Some(Span::from_position(&pos)),
+                        ));
+                    }
+                    None => {
+                        self.warnings.push(Diagnostic::warning(
+                            Some("LL004"),
+                            format!("warning: variable '{}' declared but never used", var),
+                            None,
+                        ));
+                    }
+                }
             }
         }
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::LintConfig;
+
+    #[test]
+    fn constant_overflow_beyond_i64_warns_without_panicking() {
+        // 2_000_000_000^5 overflows i64 partway through folding (each
+        // successive PRODUKT OF nests the previous one as its left
+        // operand); with raw `*` this panics on overflow in a debug
+        // build, and would silently wrap to a bogus "would wrap to X"
+        // value in release. checked_mul must instead give up on any node
+        // whose fold doesn't fit in i64, rather than reporting a wrong
+        // value for it -- the innermost node (2e9 * 2e9), which does fit
+        // in i64 but still overflows the i32 NUMBR range, is still
+        // expected to warn on its own
+        let source = "HAI 1.2\nVISIBLE PRODUKT OF PRODUKT OF PRODUKT OF PRODUKT OF 2000000000 AN 2000000000 AN 2000000000 AN 2000000000 AN 2000000000\nKTHXBYE\n";
+        let result = crate::lint_source(source, &LintConfig::default());
+        let overflow_warnings: Vec<_> = result.warnings.iter().filter(|d| d.code == Some("LL006")).collect();
+        assert_eq!(overflow_warnings.len(), 1);
+        assert!(overflow_warnings[0].message.contains("4000000000000000000"));
+    }
+
+    #[test]
+    fn constant_overflow_within_i64_still_warns() {
+        let source = "HAI 1.2\nVISIBLE SUM OF 2000000000 AN 2000000000\nKTHXBYE\n";
+        let result = crate::lint_source(source, &LintConfig::default());
+        assert!(result.warnings.iter().any(|d| d.code == Some("LL006")));
+    }
 
-    /// returns true if any errors were detected during linting
-    pub fn has_errors(&self) -> bool {
-        !self.errors.is_empty()
+    #[test]
+    fn division_by_constant_zero_through_propagation_warns_without_panicking() {
+        // the divisor here only folds to zero via constant propagation
+        // (`d` isn't a literal 0 at the QUOSHUNT OF site), exercising the
+        // same fold_numbr path check_numeric_overflow does -- fold_numbr
+        // is the one function both checks share, so synth-345's overflow
+        // fix there already covers this path too
+        let source = "HAI 1.2\nI HAS A d ITZ 0\nVISIBLE QUOSHUNT OF 10 AN d\nKTHXBYE\n";
+        let result = crate::lint_source(source, &LintConfig::default());
+        assert!(result.warnings.iter().any(|d| d.code == Some("LL023")));
     }
 }
@@ -0,0 +1,830 @@
+// lsp: a minimal language server speaking the language server protocol
+// over stdio, so editors get inline diagnostics without a wrapper plugin
+// shelling out to `lol-lint` and scraping its own cli output
+//
+// covers diagnostics-on-open/change/save (`initialize`,
+// `textDocument/didOpen`, `didChange` -- full document sync only, since
+// lolcode files are small enough that incremental sync isn't worth the
+// added bookkeeping -- `didSave`, `didClose`), navigation built on the
+// `symbols` module (`textDocument/definition`, `hover`, `references`),
+// `textDocument/codeAction` wrapping the same `fix::Suggestion`s `--fix`
+// applies from the cli, `textDocument/semanticTokens/full`,
+// `textDocument/completion`, `textDocument/inlayHint` (toggleable via
+// `initializationOptions.inlayHints` and, live,
+// `workspace/didChangeConfiguration`'s `settings.inlayHints`), and
+// `textDocument/formatting`/`rangeFormatting`, both wired to the same
+// `fmt::format_source` the `fmt` subcommand uses
+//
+// deliberately not implemented: `textDocument/signatureHelp` for `I IZ
+// func YR ...` calls. this parser has no representation of `HOW DUZ`/
+// `FOUND YR` at all -- not a stubbed-out ast variant, nothing (see
+// `cfg`'s doc comment for the same gap affecting control flow) -- so
+// there's no parameter list anywhere in this crate to draw signature
+// help from. rather than fabricate one, `signatureHelpProvider` is left
+// out of the capabilities below; a client that never advertises the
+// capability never sends the request
+
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+
+use lol_lint::ast::{self, Block, Expression, Program, Statement};
+use lol_lint::config::LintConfig;
+use lol_lint::fix::{self, Suggestion};
+use lol_lint::lexer::Lexer;
+use lol_lint::parser::Parser;
+use lol_lint::query::QueryCache;
+use lol_lint::symbols::{Symbol, SymbolTable};
+use lol_lint::types::{TokenKind, KEYWORDS};
+use serde_json::{json, Value};
+
+/// `(previous word, keyword to suggest)` pairs drawn straight from the
+/// parser's own fixed-token grammar (see `parser`'s doc comment) -- these
+/// are the spots where, having just typed the left side, only one keyword
+/// can legally come next
+const CONTEXTUAL_KEYWORDS: &[(&str, &str)] = &[
+    ("O", "RLY?"),
+    ("RLY?", "YA RLY"),
+    ("I", "HAS"),
+    ("HAS", "A"),
+    ("IM", "IN"),
+    ("IN", "YR"),
+];
+
+/// token type names published in the semantic tokens legend, in the fixed
+/// order their index appears in each token's encoded data. `function`
+/// is reserved for `HOW DUZ`/`FOUND YR`, which this parser doesn't
+/// represent yet (see `cfg`'s doc comment) -- kept in the legend so a
+/// client's highlighting theme already has a rule for it once functions
+/// land, even though nothing is tagged with it today
+const SEMANTIC_TOKEN_TYPES: &[&str] = &["keyword", "variable", "function", "string", "number", "comment"];
+
+/// runs the server until `exit` is received, or stdin closes. returns the
+/// process exit code the lsp spec expects: 0 after a clean `shutdown` then
+/// `exit`, 1 if `exit` arrives without a prior `shutdown`
+pub fn run() -> i32 {
+    let mut buffers: HashMap<String, String> = HashMap::new();
+    let mut shutdown_received = false;
+    // clients that don't care about inlay hints at all just never send
+    // `textDocument/inlayHint`; this only matters for ones that do and
+    // want them off, either from the start (`initializationOptions`) or
+    // toggled later (`workspace/didChangeConfiguration`)
+    let mut inlay_hints_enabled = true;
+    // memoizes diagnostics per document uri so a `didSave` that fires
+    // right after a `didChange` with the same text -- or a client that
+    // just echoes the buffer back unedited -- doesn't repeat the same
+    // lex/parse/lint pass (see `query`'s doc comment for the scope of
+    // what this does and doesn't cover)
+    let query_cache = QueryCache::new();
+    let stdin = io::stdin();
+    let mut reader = stdin.lock();
+
+    while let Some(message) = read_message(&mut reader) {
+        let Some(method) = message.get("method").and_then(Value::as_str) else {
+            continue; // a response to a request we never sent
+        };
+
+        match method {
+            "initialize" => {
+                if let Some(enabled) = message
+                    .pointer("/params/initializationOptions/inlayHints")
+                    .and_then(Value::as_bool)
+                {
+                    inlay_hints_enabled = enabled;
+                }
+                let id = message.get("id").cloned().unwrap_or(Value::Null);
+                write_response(
+                    id,
+                    json!({
+                        "capabilities": {
+                            "textDocumentSync": 1,
+                            "definitionProvider": true,
+                            "hoverProvider": true,
+                            "referencesProvider": true,
+                            "codeActionProvider": { "codeActionKinds": ["quickfix", "source.fixAll"] },
+                            "semanticTokensProvider": {
+                                "legend": { "tokenTypes": SEMANTIC_TOKEN_TYPES, "tokenModifiers": [] },
+                                "full": true
+                            },
+                            "completionProvider": {},
+                            "inlayHintProvider": true,
+                            "documentFormattingProvider": true,
+                            "documentRangeFormattingProvider": true
+                        },
+                        "serverInfo": { "name": "lol-lint", "version": env!("CARGO_PKG_VERSION") }
+                    }),
+                );
+            }
+            "workspace/didChangeConfiguration" => {
+                if let Some(enabled) =
+                    message.pointer("/params/settings/inlayHints").and_then(Value::as_bool)
+                {
+                    inlay_hints_enabled = enabled;
+                }
+            }
+            "textDocument/didOpen" => {
+                if let (Some(uri), Some(text)) = (
+                    message.pointer("/params/textDocument/uri").and_then(Value::as_str),
+                    message.pointer("/params/textDocument/text").and_then(Value::as_str),
+                ) {
+                    buffers.insert(uri.to_string(), text.to_string());
+                    publish_diagnostics(&query_cache, uri, text);
+                }
+            }
+            "textDocument/didChange" => {
+                if let (Some(uri), Some(text)) = (
+                    message.pointer("/params/textDocument/uri").and_then(Value::as_str),
+                    message.pointer("/params/contentChanges/0/text").and_then(Value::as_str),
+                ) {
+                    buffers.insert(uri.to_string(), text.to_string());
+                    publish_diagnostics(&query_cache, uri, text);
+                }
+            }
+            "textDocument/didSave" => {
+                if let Some(uri) = message.pointer("/params/textDocument/uri").and_then(Value::as_str) {
+                    let text = message
+                        .pointer("/params/text")
+                        .and_then(Value::as_str)
+                        .map(str::to_string)
+                        .or_else(|| buffers.get(uri).cloned());
+                    if let Some(text) = text {
+                        publish_diagnostics(&query_cache, uri, &text);
+                    }
+                }
+            }
+            "textDocument/didClose" => {
+                if let Some(uri) = message.pointer("/params/textDocument/uri").and_then(Value::as_str) {
+                    buffers.remove(uri);
+                    publish_notification(
+                        "textDocument/publishDiagnostics",
+                        json!({ "uri": uri, "diagnostics": [] }),
+                    );
+                }
+            }
+            "textDocument/definition" => {
+                let id = message.get("id").cloned().unwrap_or(Value::Null);
+                write_response(id, handle_definition(&message, &buffers).unwrap_or(Value::Null));
+            }
+            "textDocument/hover" => {
+                let id = message.get("id").cloned().unwrap_or(Value::Null);
+                write_response(id, handle_hover(&message, &buffers).unwrap_or(Value::Null));
+            }
+            "textDocument/references" => {
+                let id = message.get("id").cloned().unwrap_or(Value::Null);
+                write_response(id, handle_references(&message, &buffers).unwrap_or_else(|| json!([])));
+            }
+            "textDocument/codeAction" => {
+                let id = message.get("id").cloned().unwrap_or(Value::Null);
+                write_response(id, handle_code_action(&message, &buffers).unwrap_or_else(|| json!([])));
+            }
+            "textDocument/semanticTokens/full" => {
+                let id = message.get("id").cloned().unwrap_or(Value::Null);
+                write_response(id, handle_semantic_tokens(&message, &buffers).unwrap_or(Value::Null));
+            }
+            "textDocument/completion" => {
+                let id = message.get("id").cloned().unwrap_or(Value::Null);
+                write_response(id, handle_completion(&message, &buffers).unwrap_or_else(|| json!([])));
+            }
+            "textDocument/inlayHint" => {
+                let id = message.get("id").cloned().unwrap_or(Value::Null);
+                let hints = if inlay_hints_enabled {
+                    handle_inlay_hints(&message, &buffers).unwrap_or_else(|| json!([]))
+                } else {
+                    json!([])
+                };
+                write_response(id, hints);
+            }
+            "textDocument/formatting" | "textDocument/rangeFormatting" => {
+                let id = message.get("id").cloned().unwrap_or(Value::Null);
+                write_response(id, handle_formatting(&message, &buffers).unwrap_or_else(|| json!([])));
+            }
+            "shutdown" => {
+                shutdown_received = true;
+                let id = message.get("id").cloned().unwrap_or(Value::Null);
+                write_response(id, Value::Null);
+            }
+            "exit" => {
+                return i32::from(!shutdown_received);
+            }
+            _ => {
+                // an unhandled request still needs a response, or the
+                // client hangs waiting for one; unhandled notifications
+                // (there's no `id` to reply to) are simply ignored
+                if let Some(id) = message.get("id").cloned() {
+                    write_error(id, -32601, "method not found");
+                }
+            }
+        }
+    }
+
+    0
+}
+
+/// lints `text` and publishes the result as a `textDocument/publishDiagnostics`
+/// notification for `uri`, replacing whatever diagnostics that document had
+fn publish_diagnostics(cache: &QueryCache, uri: &str, text: &str) {
+    let result = cache.lint(uri, text, &LintConfig::default());
+
+    let diagnostics: Vec<Value> = result
+        .errors
+        .iter()
+        .map(|diagnostic| to_lsp_diagnostic(diagnostic, 1))
+        .chain(result.warnings.iter().map(|diagnostic| to_lsp_diagnostic(diagnostic, 2)))
+        .collect();
+
+    publish_notification(
+        "textDocument/publishDiagnostics",
+        json!({ "uri": uri, "diagnostics": diagnostics }),
+    );
+}
+
+/// `severity` is the lsp `DiagnosticSeverity` code: 1 for error, 2 for
+/// warning. the range's end position is derived from the diagnostic's own
+/// `start_byte`/`end_byte` span rather than hardcoded to one character
+/// wide, so the squiggle covers the whole token `span` points at -- per
+/// `ast::Position`'s own contract that's a single token (e.g. a
+/// statement's leading keyword, not necessarily the identifier inside
+/// it), so this is still an approximation, but it now tracks lol-lint's
+/// own notion of the diagnostic's location instead of a fixed constant
+fn to_lsp_diagnostic(diagnostic: &lol_lint::diagnostic::Diagnostic, severity: i32) -> Value {
+    // lsp positions are 0-based; lol-lint reports 1-based line/column
+    let start_line = diagnostic.span.map(|s| s.line).unwrap_or(1).saturating_sub(1);
+    let start_character = diagnostic.span.and_then(|s| s.column).unwrap_or(1).saturating_sub(1);
+
+    // a span's byte range and line/column both describe the same start
+    // position, so the token's width in bytes -- assumed ascii, like the
+    // rest of this lexer's column tracking -- gives the end character
+    // without a second pass over the source to recompute it
+    let width = diagnostic
+        .span
+        .and_then(|s| Some(s.end_byte? - s.start_byte?))
+        .unwrap_or(1)
+        .max(1);
+
+    json!({
+        "range": {
+            "start": { "line": start_line, "character": start_character },
+            "end": { "line": start_line, "character": start_character + width }
+        },
+        "severity": severity,
+        "code": diagnostic.code,
+        "source": "lol-lint",
+        "message": diagnostic.message,
+    })
+}
+
+/// resolves `textDocument/definition`: the symbol under the cursor's own
+/// `I HAS A` declaration, if it has one
+fn handle_definition(message: &Value, buffers: &HashMap<String, String>) -> Option<Value> {
+    let (uri, text, line, character) = doc_position(message, buffers)?;
+    let program = parse(&text);
+    let table = SymbolTable::build(&program);
+    let symbol = symbol_at(&table, line, character)?;
+    let definition = symbol.definition()?;
+    Some(json!({ "uri": uri, "range": position_range(definition) }))
+}
+
+/// resolves `textDocument/hover`: the symbol under the cursor's inferred
+/// type and initializer, read straight off its `I HAS A` declaration since
+/// lolcode variables carry no declared type of their own
+fn handle_hover(message: &Value, buffers: &HashMap<String, String>) -> Option<Value> {
+    let (_, text, line, character) = doc_position(message, buffers)?;
+    let program = parse(&text);
+    let table = SymbolTable::build(&program);
+    let symbol = symbol_at(&table, line, character)?;
+
+    let value = find_declaration(&program.body, &symbol.name);
+    let type_label = value.flatten().map_or("NOOB", infer_type);
+    let initializer = value
+        .flatten()
+        .map(render_expression)
+        .unwrap_or_else(|| "not initialized".to_string());
+
+    Some(json!({
+        "contents": {
+            "kind": "markdown",
+            "value": format!("**{}**: `{}`\n\ninitial value: `{}`", symbol.name, type_label, initializer),
+        }
+    }))
+}
+
+/// resolves `textDocument/references`: every occurrence of the symbol
+/// under the cursor, including its own declaration
+fn handle_references(message: &Value, buffers: &HashMap<String, String>) -> Option<Value> {
+    let (uri, text, line, character) = doc_position(message, buffers)?;
+    let program = parse(&text);
+    let table = SymbolTable::build(&program);
+    let symbol = symbol_at(&table, line, character)?;
+
+    let locations: Vec<Value> = symbol
+        .uses
+        .iter()
+        .map(|use_| json!({ "uri": uri, "range": position_range(&use_.pos) }))
+        .collect();
+    Some(json!(locations))
+}
+
+/// resolves `textDocument/codeAction`: one quickfix per machine-applicable
+/// suggestion whose edit starts inside the requested range, plus a
+/// standing "fix all" action bundling every suggestion in the file --
+/// mirrors the two things `lol-lint --fix` already does, just as lsp
+/// actions instead of a cli flag
+fn handle_code_action(message: &Value, buffers: &HashMap<String, String>) -> Option<Value> {
+    let uri = message.pointer("/params/textDocument/uri")?.as_str()?.to_string();
+    let text = buffers.get(&uri)?.clone();
+    let range_start = message.pointer("/params/range/start/line")?.as_u64()? as usize;
+    let range_end = message.pointer("/params/range/end/line")?.as_u64()? as usize;
+
+    let suggestions = collect_suggestions(&text);
+    if suggestions.is_empty() {
+        return Some(json!([]));
+    }
+
+    let mut actions: Vec<Value> = suggestions
+        .iter()
+        .filter(|s| {
+            let (line, _) = byte_to_position(&text, s.start_byte);
+            (range_start..=range_end).contains(&line)
+        })
+        .map(|s| {
+            json!({
+                "title": s.message,
+                "kind": "quickfix",
+                "edit": { "changes": { uri.clone(): [suggestion_to_text_edit(&text, s)] } }
+            })
+        })
+        .collect();
+
+    let edits: Vec<Value> = suggestions.iter().map(|s| suggestion_to_text_edit(&text, s)).collect();
+    actions.push(json!({
+        "title": format!("Fix all {} auto-fixable lol-lint issue(s) in this file", suggestions.len()),
+        "kind": "source.fixAll",
+        "edit": { "changes": { uri: edits } }
+    }));
+
+    Some(json!(actions))
+}
+
+/// every machine-applicable suggestion `lol-lint --fix` would compute for
+/// `text`, using its default config (an lsp client has no equivalent of
+/// the cli's `--min-identifier-length`-style flags to thread through);
+/// computed independently against the original text rather than chained
+/// like `--fix`'s own sequential apply, so two suggestions overlapping
+/// the same span (rare in practice) could conflict if applied together
+fn collect_suggestions(text: &str) -> Vec<Suggestion> {
+    let config = LintConfig::default();
+    let mut suggestions = fix::suggestions_for(text);
+    suggestions.extend(crate::keyword_casing_suggestions(text));
+    suggestions.extend(crate::missing_no_wai_suggestions(text, &config));
+    suggestions.extend(crate::duplicate_declaration_suggestions(text, &config));
+    suggestions.extend(crate::unused_declaration_suggestions(text, &config));
+    suggestions
+}
+
+fn suggestion_to_text_edit(text: &str, suggestion: &Suggestion) -> Value {
+    let (start_line, start_char) = byte_to_position(text, suggestion.start_byte);
+    let (end_line, end_char) = byte_to_position(text, suggestion.end_byte);
+    json!({
+        "range": {
+            "start": { "line": start_line, "character": start_char },
+            "end": { "line": end_line, "character": end_char }
+        },
+        "newText": suggestion.replacement
+    })
+}
+
+/// converts a byte offset into `text` to a 0-based lsp line/character,
+/// treating one byte as one character -- lolcode source is ascii by
+/// convention throughout this crate (see `suggestions_for_keyword_casing`)
+fn byte_to_position(text: &str, byte: usize) -> (usize, usize) {
+    let prefix = &text[..byte.min(text.len())];
+    match prefix.rfind('\n') {
+        Some(last_newline) => (prefix.matches('\n').count(), prefix.len() - last_newline - 1),
+        None => (0, prefix.len()),
+    }
+}
+
+/// resolves `textDocument/semanticTokens/full`: the lsp's relative-delta
+/// encoding of every token the lexer produced, tagged by
+/// [`SEMANTIC_TOKEN_TYPES`]. a multi-line `OBTW`/`TLDR` block comment is
+/// only one lexer token but the protocol requires each entry to stay on a
+/// single line, so it's clipped to its first line -- editors still shade
+/// the rest of the block as a comment from the parser's own bracket
+/// matching, just without semantic-token coverage past line one
+fn handle_semantic_tokens(message: &Value, buffers: &HashMap<String, String>) -> Option<Value> {
+    let uri = message.pointer("/params/textDocument/uri")?.as_str()?;
+    let text = buffers.get(uri)?;
+
+    let mut lexer = Lexer::new(text.clone());
+    let tokens = lexer.tokenize();
+
+    let mut data = Vec::new();
+    let mut prev_line = 0usize;
+    let mut prev_start = 0usize;
+
+    for token in &tokens {
+        let Some(type_index) = semantic_token_type(&token.kind) else {
+            continue;
+        };
+        let line = token.line.saturating_sub(1);
+        let start_char = token.column.saturating_sub(1);
+        let segment = text.get(token.start_byte..token.end_byte).unwrap_or("");
+        let length = segment.find('\n').unwrap_or(segment.len());
+
+        let delta_line = line - prev_line;
+        let delta_start = if delta_line == 0 { start_char - prev_start } else { start_char };
+        data.extend_from_slice(&[delta_line as u64, delta_start as u64, length as u64, type_index as u64, 0]);
+
+        prev_line = line;
+        prev_start = start_char;
+    }
+
+    Some(json!({ "data": data }))
+}
+
+fn semantic_token_type(kind: &TokenKind) -> Option<usize> {
+    match kind {
+        TokenKind::Keyword(_) => Some(0),
+        TokenKind::Identifier(_) => Some(1),
+        TokenKind::StringLiteral(_) => Some(3),
+        TokenKind::Number(_) => Some(4),
+        TokenKind::Comment(_) => Some(5),
+        TokenKind::Newline => None,
+    }
+}
+
+/// resolves `textDocument/completion`: any [`CONTEXTUAL_KEYWORDS`] match
+/// for the word just typed (ranked first), every lolcode keyword (ranked
+/// last), and every declared variable in between, ranked by how close its
+/// `I HAS A` declaration is to the cursor's line -- the closest thing to
+/// "scope proximity" this flat, function-less ast can express, since
+/// nothing here actually goes out of scope. no function names: `HOW
+/// DUZ`/`FOUND YR` aren't represented in the ast at all (see `cfg`'s doc
+/// comment), so there's nothing to offer after `I IZ`
+fn handle_completion(message: &Value, buffers: &HashMap<String, String>) -> Option<Value> {
+    let (_, text, line, character) = doc_position(message, buffers)?;
+    let program = parse(&text);
+    let table = SymbolTable::build(&program);
+    let prev_word = last_word_before(&text, line, character);
+
+    let mut items = Vec::new();
+
+    for (after, keyword) in CONTEXTUAL_KEYWORDS {
+        if prev_word.as_deref() == Some(*after) {
+            items.push(completion_item(keyword, 14, "0"));
+        }
+    }
+
+    let mut variables: Vec<&Symbol> = table.symbols().iter().filter(|s| s.definition().is_some()).collect();
+    variables.sort_by_key(|s| s.definition().map_or(usize::MAX, |p| line.abs_diff(p.line)));
+    for (rank, symbol) in variables.iter().enumerate() {
+        items.push(completion_item(&symbol.name, 6, &format!("1{:04}", rank)));
+    }
+
+    for keyword in KEYWORDS {
+        items.push(completion_item(keyword, 14, "2"));
+    }
+
+    Some(json!(items))
+}
+
+/// the whitespace-delimited word ending at `line`/`character` (both
+/// 1-based), or `None` at the start of an empty line
+fn last_word_before(text: &str, line: usize, character: usize) -> Option<String> {
+    let target_line = text.lines().nth(line.checked_sub(1)?)?;
+    let column = character.saturating_sub(1).min(target_line.len());
+    target_line[..column].split_whitespace().last().map(str::to_string)
+}
+
+/// `kind` is the lsp `CompletionItemKind` code: 6 for a variable, 14 for a
+/// keyword. `sort_text` controls display order within one completion list
+/// (lower sorts first) since editors don't otherwise preserve the order
+/// items arrived in
+fn completion_item(label: &str, kind: i32, sort_text: &str) -> Value {
+    json!({ "label": label, "kind": kind, "sortText": sort_text })
+}
+
+/// resolves `textDocument/inlayHint`: a `: TYPE` hint after every `I HAS
+/// A` declaration and an `IT: TYPE` hint after every standalone
+/// expression statement (which lolcode assigns to the implicit `IT`),
+/// using the same best-effort [`infer_type`] `hover` already relies on
+fn handle_inlay_hints(message: &Value, buffers: &HashMap<String, String>) -> Option<Value> {
+    let uri = message.pointer("/params/textDocument/uri")?.as_str()?;
+    let text = buffers.get(uri)?;
+    let program = parse(text);
+
+    let mut hints = Vec::new();
+    collect_inlay_hints(&program.body, &mut hints);
+
+    let items: Vec<Value> = hints
+        .into_iter()
+        .map(|(byte, label)| {
+            let (line, character) = byte_to_position(text, byte);
+            json!({
+                "position": { "line": line, "character": character },
+                "label": label,
+                "paddingLeft": true,
+            })
+        })
+        .collect();
+    Some(json!(items))
+}
+
+/// walks `block`, including nested `O RLY?`/`IM IN YR LOOP` bodies,
+/// collecting `(byte offset to hint after, hint label)` pairs for every
+/// declaration and expression statement
+fn collect_inlay_hints(block: &Block, hints: &mut Vec<(usize, String)>) {
+    for stmt in &block.statements {
+        match stmt {
+            Statement::Declaration { value, pos, .. } => {
+                let type_label = value.as_ref().map_or("NOOB", infer_type);
+                hints.push((pos.end_byte, format!(": {type_label}")));
+            }
+            Statement::Expr { expression, pos } => {
+                hints.push((pos.end_byte, format!("IT: {}", infer_type(expression))));
+            }
+            Statement::ORly { ya_rly, no_wai, .. } => {
+                collect_inlay_hints(ya_rly, hints);
+                if let Some(block) = no_wai {
+                    collect_inlay_hints(block, hints);
+                }
+            }
+            Statement::Loop { body, .. } => collect_inlay_hints(body, hints),
+            _ => {}
+        }
+    }
+}
+
+/// resolves both `textDocument/formatting` and `textDocument/rangeFormatting`
+/// to the same edit: the whole document rewritten with
+/// `fmt::format_source`. `fmt`'s indentation for any one line depends on
+/// every `O RLY?`/`IM IN YR LOOP` opened before it in the file, so a
+/// requested range can't be reformatted in isolation without also
+/// replaying everything above it -- reformatting the whole document and
+/// letting the editor apply that single edit gets the same end state a
+/// true range-limited reformat would, just without pretending the edit
+/// is scoped to the requested range
+fn handle_formatting(message: &Value, buffers: &HashMap<String, String>) -> Option<Value> {
+    let uri = message.pointer("/params/textDocument/uri")?.as_str()?;
+    let text = buffers.get(uri)?;
+
+    let config = uri
+        .strip_prefix("file://")
+        .map(|path| crate::editorconfig::resolve(Path::new(path)))
+        .unwrap_or_default();
+    let formatted = crate::fmt::format_source(text, &config);
+    if formatted == *text {
+        return Some(json!([]));
+    }
+
+    let (end_line, end_char) = byte_to_position(text, text.len());
+    Some(json!([{
+        "range": {
+            "start": { "line": 0, "character": 0 },
+            "end": { "line": end_line, "character": end_char }
+        },
+        "newText": formatted
+    }]))
+}
+
+/// pulls `textDocument.uri` and the buffered text for it, plus the
+/// request's cursor position converted from lsp's 0-based line/character
+/// to this crate's 1-based `ast::Position` line/column
+fn doc_position(message: &Value, buffers: &HashMap<String, String>) -> Option<(String, String, usize, usize)> {
+    let uri = message.pointer("/params/textDocument/uri")?.as_str()?.to_string();
+    let text = buffers.get(&uri)?.clone();
+    let line = message.pointer("/params/position/line")?.as_u64()? as usize + 1;
+    let character = message.pointer("/params/position/character")?.as_u64()? as usize + 1;
+    Some((uri, text, line, character))
+}
+
+fn parse(text: &str) -> Program {
+    let mut lexer = Lexer::new(text.to_string());
+    let tokens = lexer.tokenize();
+    let mut parser = Parser::new(tokens);
+    parser.parse_program()
+}
+
+/// the first symbol in `table` with an occurrence covering `line`/`character`
+/// (both 1-based, matching `ast::Position`)
+fn symbol_at(table: &SymbolTable, line: usize, character: usize) -> Option<&Symbol> {
+    table
+        .symbols()
+        .iter()
+        .find(|symbol| symbol.uses.iter().any(|use_| position_contains(&use_.pos, line, character)))
+}
+
+fn position_contains(pos: &ast::Position, line: usize, character: usize) -> bool {
+    let width = (pos.end_byte.saturating_sub(pos.start_byte)).max(1);
+    pos.line == line && character >= pos.column && character < pos.column + width
+}
+
+/// converts an `ast::Position`'s 1-based line/column and byte range into
+/// an lsp range with 0-based line/character
+fn position_range(pos: &ast::Position) -> Value {
+    let width = pos.end_byte.saturating_sub(pos.start_byte).max(1);
+    let line = pos.line.saturating_sub(1);
+    let character = pos.column.saturating_sub(1);
+    json!({
+        "start": { "line": line, "character": character },
+        "end": { "line": line, "character": character + width }
+    })
+}
+
+/// finds `name`'s `I HAS A` declaration anywhere in `block` (including
+/// nested `O RLY?`/`IM IN YR LOOP` bodies) and returns its initializer,
+/// `Some(None)` for a declaration with no initializer, or `None` if `name`
+/// is never declared in `block` at all
+fn find_declaration<'a>(block: &'a Block, name: &str) -> Option<Option<&'a Expression>> {
+    for stmt in &block.statements {
+        match stmt {
+            Statement::Declaration { name: n, value, .. } if n == name => {
+                return Some(value.as_ref());
+            }
+            Statement::ORly { ya_rly, no_wai, .. } => {
+                if let Some(found) = find_declaration(ya_rly, name) {
+                    return Some(found);
+                }
+                if let Some(block) = no_wai {
+                    if let Some(found) = find_declaration(block, name) {
+                        return Some(found);
+                    }
+                }
+            }
+            Statement::Loop { body, .. } => {
+                if let Some(found) = find_declaration(body, name) {
+                    return Some(found);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// a best-effort lolcode type name for `expr`'s result, since declarations
+/// carry no type annotation of their own
+fn infer_type(expr: &Expression) -> &'static str {
+    match expr {
+        Expression::Number(n, _) if n.contains('.') => "NUMBAR",
+        Expression::Number(_, _) => "NUMBR",
+        Expression::String(_, _) => "YARN",
+        Expression::Identifier(_, _) => "unknown (copies another variable)",
+        Expression::Sum { .. }
+        | Expression::Diff { .. }
+        | Expression::Produkt { .. }
+        | Expression::Quoshunt { .. }
+        | Expression::Mod { .. } => "NUMBR",
+        Expression::BothSaem { .. } | Expression::Diffrint { .. } => "TROOF",
+    }
+}
+
+/// renders `expr` back to roughly the lolcode source that produced it, for
+/// display in a hover tooltip; compound expressions are summarized by
+/// their operation rather than fully reconstructed
+fn render_expression(expr: &Expression) -> String {
+    match expr {
+        Expression::Number(n, _) => n.clone(),
+        Expression::String(s, _) => format!("\"{}\"", s),
+        Expression::Identifier(name, _) => name.clone(),
+        Expression::Sum { .. } => "SUM OF ...".to_string(),
+        Expression::Diff { .. } => "DIFF OF ...".to_string(),
+        Expression::Produkt { .. } => "PRODUKT OF ...".to_string(),
+        Expression::Quoshunt { .. } => "QUOSHUNT OF ...".to_string(),
+        Expression::Mod { .. } => "MOD OF ...".to_string(),
+        Expression::BothSaem { .. } => "BOTH SAEM ...".to_string(),
+        Expression::Diffrint { .. } => "DIFFRINT ...".to_string(),
+    }
+}
+
+fn write_response(id: Value, result: Value) {
+    write_message(&json!({ "jsonrpc": "2.0", "id": id, "result": result }));
+}
+
+fn write_error(id: Value, code: i32, message: &str) {
+    write_message(&json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } }));
+}
+
+fn publish_notification(method: &str, params: Value) {
+    write_message(&json!({ "jsonrpc": "2.0", "method": method, "params": params }));
+}
+
+/// writes `value` to stdout framed with the `Content-Length` header the
+/// lsp spec requires, since it speaks newline-agnostic json-rpc rather
+/// than the line-delimited json every other `--json` output uses
+fn write_message(value: &Value) {
+    let body = serde_json::to_string(value).unwrap_or_default();
+    let mut stdout = io::stdout();
+    let _ = write!(stdout, "Content-Length: {}\r\n\r\n{}", body.len(), body);
+    let _ = stdout.flush();
+}
+
+/// reads one `Content-Length`-framed json-rpc message from `reader`,
+/// returning `None` once stdin closes or a message can't be parsed
+fn read_message<R: BufRead>(reader: &mut R) -> Option<Value> {
+    let mut content_length: Option<usize> = None;
+
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).ok()? == 0 {
+            return None; // stdin closed
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some(value) = line.strip_prefix("Content-Length:") {
+            content_length = value.trim().parse().ok();
+        }
+    }
+
+    let mut body = vec![0u8; content_length?];
+    reader.read_exact(&mut body).ok()?;
+    serde_json::from_slice(&body).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lol_lint::diagnostic::{Diagnostic, Span};
+
+    #[test]
+    fn to_lsp_diagnostic_widens_the_range_to_the_span_byte_width() {
+        let span = Span { line: 3, column: Some(5), start_byte: Some(20), end_byte: Some(28) };
+        let diagnostic = Diagnostic::warning(Some("LL001"), "warning: unused variable".to_string(), Some(span));
+        let json = to_lsp_diagnostic(&diagnostic, 2);
+        assert_eq!(json["range"]["start"]["line"], 2); // 0-based
+        assert_eq!(json["range"]["start"]["character"], 4);
+        assert_eq!(json["range"]["end"]["character"], 4 + 8);
+    }
+
+    #[test]
+    fn byte_to_position_counts_newlines_before_the_offset() {
+        let text = "HAI 1.2\nVISIBLE 1\nKTHXBYE\n";
+        assert_eq!(byte_to_position(text, 0), (0, 0));
+        // byte 8 is the 'V' of the second line
+        assert_eq!(byte_to_position(text, 8), (1, 0));
+    }
+
+    #[test]
+    fn semantic_token_type_has_no_entry_for_newline_tokens() {
+        assert!(semantic_token_type(&TokenKind::Newline).is_none());
+        assert!(semantic_token_type(&TokenKind::Keyword("HAI".to_string())).is_some());
+    }
+
+    #[test]
+    fn position_contains_treats_a_zero_width_span_as_one_character_wide() {
+        let pos = ast::Position { line: 2, column: 3, start_byte: 10, end_byte: 10 };
+        assert!(position_contains(&pos, 2, 3));
+        assert!(!position_contains(&pos, 2, 4));
+        assert!(!position_contains(&pos, 3, 3));
+    }
+
+    #[test]
+    fn position_range_converts_to_zero_based_lsp_coordinates() {
+        let pos = ast::Position { line: 1, column: 1, start_byte: 0, end_byte: 3 };
+        let range = position_range(&pos);
+        assert_eq!(range["start"]["line"], 0);
+        assert_eq!(range["start"]["character"], 0);
+        assert_eq!(range["end"]["character"], 3);
+    }
+
+    #[test]
+    fn find_declaration_locates_a_declaration_nested_inside_o_rly() {
+        let program = parse("HAI 1.2\nBOTH SAEM 1 AN 1\nO RLY?\n    YA RLY\n        I HAS A x ITZ 5\nOIC\nKTHXBYE\n");
+        let found = find_declaration(&program.body, "x");
+        assert!(matches!(found, Some(Some(Expression::Number(n, _))) if n == "5"));
+    }
+
+    #[test]
+    fn find_declaration_returns_none_for_an_undeclared_name() {
+        let program = parse("HAI 1.2\nVISIBLE \"hi\"\nKTHXBYE\n");
+        assert!(find_declaration(&program.body, "x").is_none());
+    }
+
+    #[test]
+    fn infer_type_and_render_expression_describe_a_produkt_expression() {
+        let program = parse("HAI 1.2\nI HAS A x ITZ PRODUKT OF 2 AN 3\nKTHXBYE\n");
+        let Some(Some(expr)) = find_declaration(&program.body, "x") else {
+            panic!("x should be declared with an initializer");
+        };
+        assert_eq!(infer_type(expr), "NUMBR");
+        assert_eq!(render_expression(expr), "PRODUKT OF ...");
+    }
+
+    #[test]
+    fn last_word_before_splits_on_whitespace_up_to_the_cursor() {
+        let text = "VISIBLE x\n";
+        assert_eq!(last_word_before(text, 1, 10), Some("x".to_string()));
+        assert_eq!(last_word_before(text, 1, 1), None);
+    }
+
+    #[test]
+    fn read_message_parses_a_content_length_framed_body() {
+        let body = r#"{"jsonrpc":"2.0","id":1,"method":"shutdown"}"#;
+        let framed = format!("Content-Length: {}\r\n\r\n{}", body.len(), body);
+        let mut reader = std::io::BufReader::new(framed.as_bytes());
+        let message = read_message(&mut reader).expect("should parse a full frame");
+        assert_eq!(message["method"], "shutdown");
+    }
+}
@@ -1,20 +1,40 @@
 // lol-lint: a strict linter for lolcode
 // enforces syntax rules and performs semantic analysis
 
-mod ast;
-mod lexer;
-mod linter;
-mod parser;
-mod types;
+mod cache;
+mod clones;
+mod compare;
+mod daemon;
+mod editorconfig;
+mod eval;
+mod fmt;
+mod grep;
+mod hierconfig;
+mod ignore;
+mod interpreter;
+mod lsp;
+mod merge;
+mod minify;
+mod rewrite;
+mod transpile;
+mod upgrade;
 
 use clap::Parser as ClapParser;
+use clap::ValueEnum;
 use colored::*;
-use lexer::Lexer;
-use linter::Linter;
-use parser::Parser;
+use lol_lint::config::LintConfig;
+use lol_lint::diagnostic::Diagnostic;
+use lol_lint::lexer::Lexer;
+use lol_lint::linter::Linter;
+use lol_lint::parser::Parser;
+use lol_lint::{ast, fix, rules};
+use rayon::prelude::*;
 use serde::Serialize;
 use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
 use std::process;
+use std::process::Command;
 
 /// command-line interface structure for argument parsing
 #[derive(ClapParser)]
@@ -22,75 +42,2263 @@ use std::process;
 #[command(version = "0.1.0")]
 #[command(about = "A linter for LOLCODE", long_about = None)]
 struct Cli {
-    /// input lolcode file to lint
+    /// input lolcode file(s) to lint; supports glob patterns like `examples/*.lol`,
+    /// or `-` to read a single file's source from standard input
+    #[arg(required_unless_present_any = ["output_schema", "list_rules", "files_from"])]
+    files: Vec<String>,
+
+    /// output results as json for ci/cd integration
+    #[arg(long)]
+    json: bool,
+
+    /// show code statistics (loc, variables, loops, etc.)
+    #[arg(long)]
+    stats: bool,
+
+    /// when to use colored output: auto (default) follows terminal
+    /// detection and the NO_COLOR/CLICOLOR_FORCE environment variables,
+    /// always forces color, never disables it
+    #[arg(long, value_enum, default_value_t = ColorChoice::Auto)]
+    color: ColorChoice,
+
+    /// show debug information including tokens and ast
+    #[arg(long)]
+    debug: bool,
+
+    /// enable the recommended bundle of optional style checks
+    #[arg(long)]
+    style: bool,
+
+    /// warn on identifiers shorter than this many characters (0 disables)
+    #[arg(long, default_value_t = 0)]
+    min_identifier_length: usize,
+
+    /// warn on identifiers longer than this many characters (0 disables)
+    #[arg(long, default_value_t = 0)]
+    max_identifier_length: usize,
+
+    /// warn on trailing whitespace and mixed tab/space indentation
+    #[arg(long)]
+    check_whitespace: bool,
+
+    /// rewrite each file to correct the formatting issues lol-lint knows
+    /// how to fix mechanically (trailing whitespace, missing final
+    /// newline), then lint the corrected source; has no effect reading
+    /// from stdin, since there's nothing on disk to write back to
+    #[arg(long)]
+    fix: bool,
+
+    /// with --fix, print a colored unified diff of what would change per
+    /// file instead of writing anything; no effect without --fix
+    #[arg(long)]
+    dry_run: bool,
+
+    /// with --fix, walk through each fixable diagnostic one at a time,
+    /// showing its diff and prompting accept/skip/accept-all/quit, instead
+    /// of applying every available fix at once; no effect without --fix
+    #[arg(long)]
+    interactive: bool,
+
+    /// with --fix, write every fix as a standard unified diff to this path
+    /// instead of modifying files, so it can be reviewed in code review
+    /// tooling or applied later with `git apply`; no effect without --fix
+    #[arg(long)]
+    emit_patch: Option<String>,
+
+    /// warn if comment-to-code line ratio falls below this percentage (0 disables)
+    #[arg(long, default_value_t = 0.0)]
+    min_comment_density: f64,
+
+    /// require declarations to appear before other statements in a block
+    #[arg(long)]
+    declarations_at_top: bool,
+
+    /// real path to report for stdin input (only meaningful with `-`)
+    #[arg(long)]
+    stdin_filename: Option<String>,
+
+    /// alternate output format, for editor and ci integrations
+    #[arg(long, value_enum)]
+    format: Option<OutputFormat>,
+
+    /// print the json schema for `--json` output and exit
+    #[arg(long)]
+    output_schema: bool,
+
+    /// hide warnings from output; errors are still shown and warnings are
+    /// still counted in the summary
+    #[arg(long)]
+    quiet: bool,
+
+    /// cache lint results on disk, keyed by file content and configuration
+    #[arg(long)]
+    cache: bool,
+
+    /// directory to store the on-disk lint cache in
+    #[arg(long, default_value = ".lol-lint-cache")]
+    cache_dir: String,
+
+    /// number of worker threads for parallel linting (0 uses all cores)
+    #[arg(long, default_value_t = 0)]
+    threads: usize,
+
+    /// glob or bare-name pattern to exclude from directory/glob expansion,
+    /// on top of any patterns in `.lollintignore` (repeatable)
+    #[arg(long = "ignore-pattern")]
+    ignore_pattern: Vec<String>,
+
+    /// list every rule's code, name, default severity, fixability, and a
+    /// one-line summary, then exit; combine with --json for machine output
+    #[arg(long)]
+    list_rules: bool,
+
+    /// after the diagnostics, print a table of rule code to diagnostic
+    /// count across all linted files, sorted most-frequent first
+    #[arg(long)]
+    rule_summary: bool,
+
+    /// group the human-readable report by file (default), rule, or
+    /// severity instead of the flat per-file errors-then-warnings dump
+    #[arg(long, value_enum)]
+    group_by: Option<GroupBy>,
+
+    /// sort diagnostics within each group by source location (default),
+    /// severity, or rule code
+    #[arg(long, value_enum)]
+    sort_by: Option<SortBy>,
+
+    /// stop emitting diagnostics after this many errors (and, separately,
+    /// this many warnings) per file, printing a "... and N more" notice;
+    /// 0 disables the cap
+    #[arg(long, default_value_t = 0)]
+    max_diagnostics: usize,
+
+    /// abandon a single file's analysis after this many seconds, reporting
+    /// an "analysis timed out" error instead of hanging the whole run;
+    /// 0 disables the timeout
+    #[arg(long, default_value_t = 0)]
+    timeout_per_file: u64,
+
+    /// restrict emitted diagnostics to an inclusive line range, e.g.
+    /// `40:80` (repeatable); the whole file is still analyzed for
+    /// correctness, only the report is filtered, for "lint only what I'm
+    /// editing" editor integrations. diagnostics without a line number are
+    /// always shown, since there's no position to filter on
+    #[arg(long = "range")]
+    range: Vec<String>,
+
+    /// read additional file paths, one per line, from a file or (with `-`)
+    /// standard input, on top of any given as arguments; lets pre-commit
+    /// and similar hook frameworks pass exactly the staged files without
+    /// relying on shell globbing
+    #[arg(long)]
+    files_from: Option<String>,
+
+    /// only report diagnostics on lines changed versus a git ref (default
+    /// `HEAD` if no ref is given); shells out to `git diff -U0`, so this is
+    /// only useful inside a git checkout. lets a large legacy codebase
+    /// adopt lol-lint incrementally by only holding new/touched lines to
+    /// account
+    #[arg(long, num_args = 0..=1, default_missing_value = "HEAD")]
+    diff: Option<String>,
+
+    /// dump the token stream or parsed ast as json instead of linting, so
+    /// external tools, graders, and tests (and, for `tokens`, syntax
+    /// highlighters) can consume the structure -- including byte spans --
+    /// rather than scraping `--debug`'s `Debug`-formatted text
+    #[arg(long, value_enum)]
+    emit: Option<EmitKind>,
+}
+
+/// structural representation `--emit` prints instead of running the linter
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum EmitKind {
+    /// the token stream, one json object per file
+    Tokens,
+    /// the parsed ast, one json object per file
+    Ast,
+    /// the symbol table (every variable's definitions and uses), one json
+    /// object per file
+    Symbols,
+    /// which functions call which, as graphviz dot; see `run_emit`'s
+    /// `Callgraph` arm for why this is currently always an empty graph
+    Callgraph,
+    /// which variables feed into which, as graphviz dot: an edge `x -> y`
+    /// means `x`'s value was read while computing what got stored into `y`
+    Depgraph,
+    /// the parse tree as graphviz dot, one node per statement/expression
+    /// labeled with its kind, key fields, and source position -- a
+    /// browsable alternative to `--emit ast`'s json or `--debug`'s
+    /// `Debug`-formatted dump
+    AstDot,
+    /// the `cfg` module's basic-block graph as graphviz dot: one node per
+    /// basic block, `O RLY?` branches labeled `YA RLY`/`NO WAI`; see
+    /// `run_emit`'s `CfgDot` arm for why loop back-edges are left unlabeled
+    CfgDot,
+}
+
+/// reads newline-separated file paths from a path (or standard input, when
+/// given `-`), skipping blank lines
+fn read_files_from(source: &str) -> Vec<String> {
+    let content = if source == "-" {
+        use std::io::Read;
+        let mut buf = String::new();
+        let _ = std::io::stdin().read_to_string(&mut buf);
+        buf
+    } else {
+        fs::read_to_string(source).unwrap_or_default()
+    };
+
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// parses `--range` arguments of the form `start:end`, discarding any that
+/// don't parse rather than failing the whole run over a typo
+fn parse_ranges(raw: &[String]) -> Vec<(usize, usize)> {
+    raw.iter()
+        .filter_map(|spec| {
+            let (start, end) = spec.split_once(':')?;
+            Some((start.trim().parse().ok()?, end.trim().parse().ok()?))
+        })
+        .collect()
+}
+
+/// keeps diagnostics whose line falls in one of the given ranges (or that
+/// carry no position at all, since they can't be filtered); an empty range
+/// list disables filtering entirely
+fn filter_by_range(list: Vec<Diagnostic>, ranges: &[(usize, usize)]) -> Vec<Diagnostic> {
+    if ranges.is_empty() {
+        return list;
+    }
+
+    list.into_iter()
+        .filter(|diagnostic| match diagnostic.span.map(|s| s.line) {
+            Some(line) => ranges.iter().any(|&(start, end)| line >= start && line <= end),
+            None => true,
+        })
+        .collect()
+}
+
+/// runs `git diff -U0 <base> -- <file>` and parses the hunk headers to find
+/// which lines of `file`'s working-tree version were added or modified;
+/// returns `None` if git isn't available or the command fails, so `--diff`
+/// degrades to "lint everything" rather than hiding a file's diagnostics
+/// entirely, but `Some(vec![])` for a successful diff with no hunks, since
+/// an unchanged file should report nothing under `--diff`
+fn git_diff_ranges(base: &str, file: &str) -> Option<Vec<(usize, usize)>> {
+    let output = Command::new("git")
+        .args(["diff", "--no-color", "-U0", base, "--", file])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Some(
+        stdout
+            .lines()
+            .filter_map(|line| {
+                // hunk headers look like `@@ -12,3 +15,5 @@ ...`; we only care
+                // about the `+` side, which describes the new file's lines
+                let plus = line.strip_prefix("@@ -")?.split('+').nth(1)?;
+                let spec = plus.split_whitespace().next()?;
+                let (start_str, count_str) = spec.split_once(',').unwrap_or((spec, "1"));
+                let start: usize = start_str.parse().ok()?;
+                let count: usize = count_str.parse().ok()?;
+                if count == 0 {
+                    // a pure deletion has no added lines to report against
+                    return None;
+                }
+                Some((start, start + count - 1))
+            })
+            .collect(),
+    )
+}
+
+/// how to cluster diagnostics in the human-readable report
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum GroupBy {
+    File,
+    Rule,
+    Severity,
+}
+
+/// how to order diagnostics within a group
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum SortBy {
+    Location,
+    Severity,
+    Rule,
+}
+
+/// when to emit ansi color codes
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum ColorChoice {
+    /// follow terminal detection and the NO_COLOR/CLICOLOR_FORCE env vars
+    Auto,
+    /// always emit color, even when piped
+    Always,
+    /// never emit color
+    Never,
+}
+
+/// resolves `--color` against stdout tty detection and the NO_COLOR and
+/// CLICOLOR_FORCE conventions, then applies the result globally. precedence
+/// (highest first): explicit `--color always`/`--color never`, then
+/// CLICOLOR_FORCE (forces color on even when piped), then NO_COLOR, then
+/// tty detection
+fn apply_color_choice(choice: ColorChoice) {
+    use std::io::IsTerminal;
+
+    let enabled = match choice {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => {
+            if std::env::var_os("CLICOLOR_FORCE").is_some_and(|v| v != "0") {
+                true
+            } else if std::env::var_os("NO_COLOR").is_some() {
+                false
+            } else {
+                std::io::stdout().is_terminal()
+            }
+        }
+    };
+
+    colored::control::set_override(enabled);
+}
+
+/// output formats beyond the default human-readable and `--json` views
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum OutputFormat {
+    /// github actions `::error file=...,line=...,col=...::message` annotations
+    Github,
+    /// gitlab code quality report json artifact
+    Gitlab,
+    /// reviewdog diagnostic format (rdjson)
+    Rdjson,
+    /// vim/emacs quickfix `file:line:col: severity: message` lines
+    Quickfix,
+    /// one colored line per diagnostic, no headers or summary
+    Compact,
+    /// the default multi-line human-readable report (headers, summary, stats)
+    Full,
+    /// rustc-style report with the offending source line and a caret
+    Rustc,
+    /// one json object per diagnostic, streamed as each file finishes
+    Jsonl,
+    /// accessible plain-text report: no color, box drawing, carets, or
+    /// glyphs, with severity and position spelled out in words
+    Plain,
+}
+
+/// a single line of `--format jsonl` output
+#[derive(Serialize)]
+struct JsonlDiagnostic<'a> {
+    file: &'a str,
+    severity: &'a str,
+    message: &'a str,
+}
+
+/// json output format for machine-readable results
+#[derive(Serialize)]
+struct JsonOutput {
     file: String,
+    errors: Vec<String>,
+    warnings: Vec<String>,
+    /// errors cut by `--max-diagnostics`; the true total is errors.len() + this
+    errors_truncated: usize,
+    /// warnings cut by `--max-diagnostics`; the true total is warnings.len() + this
+    warnings_truncated: usize,
+    stats: Option<Stats>,
+    /// machine-applicable edits, matched to a warning by its message text;
+    /// empty when nothing in this file is auto-fixable
+    suggestions: Vec<fix::Suggestion>,
+}
+
+/// current version of the `--json` report shape; bump whenever a field is
+/// added, removed, or changes meaning so integrators can detect drift
+const JSON_SCHEMA_VERSION: u32 = 2;
+
+/// top-level `--json` report: a versioned envelope around each file's result
+#[derive(Serialize)]
+struct JsonReport {
+    schema_version: u32,
+    files: Vec<JsonOutput>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rule_summary: Option<Vec<RuleCount>>,
+}
+
+/// how many diagnostics a given rule code produced across all linted files
+#[derive(Serialize)]
+struct RuleCount {
+    code: &'static str,
+    count: usize,
+}
+
+/// counts every diagnostic across all results by rule code and returns the
+/// counts sorted most-frequent first; diagnostics with no code (not tied to
+/// a specific rule) are dropped rather than shown under a misleading code
+fn summarize_rules(results: &[FileResult]) -> Vec<RuleCount> {
+    let mut counts: std::collections::HashMap<&'static str, usize> = std::collections::HashMap::new();
+
+    for result in results {
+        for diagnostic in result.errors.iter().chain(&result.warnings) {
+            if let Some(code) = diagnostic.code {
+                *counts.entry(code).or_insert(0) += 1;
+            }
+        }
+    }
+
+    let mut counts: Vec<RuleCount> = counts
+        .into_iter()
+        .map(|(code, count)| RuleCount { code, count })
+        .collect();
+    counts.sort_by(|a, b| b.count.cmp(&a.count).then(a.code.cmp(b.code)));
+    counts
+}
+
+/// prints the rule-code-to-count table, most-frequent first
+fn print_rule_summary(counts: &[RuleCount]) {
+    if counts.is_empty() {
+        return;
+    }
+
+    println!();
+    println!("{}", "--- Rule Summary ---".cyan().bold());
+    for entry in counts {
+        println!("{:<7} {}", entry.code, entry.count);
+    }
+}
+
+/// code statistics collected during ast traversal
+#[derive(Serialize, Clone)]
+struct Stats {
+    lines_of_code: usize,
+    variables: usize,
+    loops: usize,
+    conditionals: usize,
+    expressions: usize,
+}
+
+/// a single entry in a gitlab code quality report
+#[derive(Serialize)]
+struct GitlabIssue {
+    description: String,
+    check_name: String,
+    fingerprint: String,
+    severity: String,
+    location: GitlabLocation,
+}
+
+#[derive(Serialize)]
+struct GitlabLocation {
+    path: String,
+    lines: GitlabLines,
+}
+
+#[derive(Serialize)]
+struct GitlabLines {
+    begin: usize,
+}
+
+/// top-level rdjson document consumed by reviewdog
+#[derive(Serialize)]
+struct RdjsonReport {
+    source: RdjsonSource,
+    diagnostics: Vec<RdjsonDiagnostic>,
+}
+
+#[derive(Serialize)]
+struct RdjsonSource {
+    name: String,
+}
+
+#[derive(Serialize)]
+struct RdjsonDiagnostic {
+    message: String,
+    location: RdjsonLocation,
+    severity: String,
+}
+
+#[derive(Serialize)]
+struct RdjsonLocation {
+    path: String,
+    range: RdjsonRange,
+}
+
+#[derive(Serialize)]
+struct RdjsonRange {
+    start: RdjsonPosition,
+}
+
+#[derive(Serialize)]
+struct RdjsonPosition {
+    line: usize,
+    column: usize,
+}
+
+/// outcome of linting a single file, independent of output format
+struct FileResult {
+    file: String,
+    errors: Vec<Diagnostic>,
+    warnings: Vec<Diagnostic>,
+    /// warnings.len() before `--quiet` cleared them, for accurate summaries
+    warning_total: usize,
+    /// errors cut by `--max-diagnostics`
+    errors_truncated: usize,
+    /// warnings cut by `--max-diagnostics`
+    warnings_truncated: usize,
+    stats: Option<Stats>,
+    /// machine-applicable edits for the warnings that have one (see
+    /// `fix::suggestions_for`); empty when nothing here is auto-fixable
+    suggestions: Vec<fix::Suggestion>,
+    /// the file could not be read or parsed at all
+    fatal: bool,
+    /// with `--fix --emit-patch`, the unified diff of what would change in
+    /// this file; `None` when the flag wasn't given or nothing changed
+    patch: Option<String>,
+}
+
+/// keeps at most `max` entries (0 means unlimited), returning the kept
+/// list alongside how many were cut off the end
+fn truncate_diagnostics(mut list: Vec<Diagnostic>, max: usize) -> (Vec<Diagnostic>, usize) {
+    if max == 0 || list.len() <= max {
+        (list, 0)
+    } else {
+        let truncated = list.len() - max;
+        list.truncate(max);
+        (list, truncated)
+    }
+}
+
+fn main() {
+    // `compare` is a small, self-contained sibling command rather than a
+    // clap subcommand: every other flag here applies to a single lint run
+    // over `files`, and folding compare's two positional report paths into
+    // that same struct would force them to be optional everywhere else
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("compare") {
+        let (Some(old), Some(new)) = (args.get(2), args.get(3)) else {
+            eprintln!("usage: lol-lint compare <old.json> <new.json>");
+            process::exit(2);
+        };
+        process::exit(compare::run(old, new));
+    }
+    if args.get(1).map(String::as_str) == Some("merge") {
+        process::exit(merge::run(&args[2..]));
+    }
+    if args.get(1).map(String::as_str) == Some("explain") {
+        let Some(code) = args.get(2) else {
+            eprintln!("usage: lol-lint explain <RULE_CODE>");
+            process::exit(2);
+        };
+        process::exit(rules::explain(code));
+    }
+    if args.get(1).map(String::as_str) == Some("fmt") {
+        process::exit(fmt::run(&args[2..]));
+    }
+    if args.get(1).map(String::as_str) == Some("lsp") {
+        process::exit(lsp::run());
+    }
+    if args.get(1).map(String::as_str) == Some("daemon") {
+        process::exit(daemon::run(&args[2..]));
+    }
+    if args.get(1).map(String::as_str) == Some("run") {
+        process::exit(interpreter::run(&args[2..]));
+    }
+    if args.get(1).map(String::as_str) == Some("eval") {
+        process::exit(eval::run(&args[2..]));
+    }
+    if args.get(1).map(String::as_str) == Some("transpile") {
+        process::exit(transpile::run(&args[2..]));
+    }
+    if args.get(1).map(String::as_str) == Some("minify") {
+        process::exit(minify::run(&args[2..]));
+    }
+    if args.get(1).map(String::as_str) == Some("rewrite") {
+        process::exit(rewrite::run(&args[2..]));
+    }
+    if args.get(1).map(String::as_str) == Some("upgrade") {
+        process::exit(upgrade::run(&args[2..]));
+    }
+    if args.get(1).map(String::as_str) == Some("grep") {
+        process::exit(grep::run(&args[2..]));
+    }
+    if args.get(1).map(String::as_str) == Some("clones") {
+        process::exit(clones::run(&args[2..]));
+    }
+    // `fix` is a reserved subcommand name for a planned standalone autofix
+    // engine; it's recognized here so the CLI surface is settled now, but
+    // it currently just says so rather than pretending to work. `--fix` as
+    // a flag on `check` is the real autofix entry point today
+    if let Some(name @ "fix") = args.get(1).map(String::as_str) {
+        eprintln!("lol-lint {name}: not implemented yet in this version");
+        process::exit(2);
+    }
+
+    // bare `lol-lint <files...>` is an alias for `lol-lint check <files...>`;
+    // strip a leading `check` so clap sees the same argument shape either way
+    let mut args = args;
+    if args.get(1).map(String::as_str) == Some("check") {
+        args.remove(1);
+    }
+
+    let cli = Cli::parse_from(args);
+
+    if cli.output_schema {
+        print_output_schema();
+        return;
+    }
+
+    if cli.list_rules {
+        print_rule_list(cli.json);
+        return;
+    }
+
+    apply_color_choice(cli.color);
+
+    let mut ignore_patterns = ignore::load_ignore_file(Path::new(".lollintignore"));
+    ignore_patterns.extend(cli.ignore_pattern.iter().cloned());
+
+    let mut file_args = cli.files.clone();
+    if let Some(source) = &cli.files_from {
+        file_args.extend(read_files_from(source));
+    }
+    let files = resolve_files(&file_args, &ignore_patterns);
+
+    if let Some(emit) = cli.emit {
+        process::exit(run_emit(emit, &files));
+    }
+
+    // lex/parse/lint every file on a thread pool; par_iter over a Vec keeps
+    // the collected results in the original file order regardless of which
+    // worker finishes first, so output stays deterministic. --threads bounds
+    // the pool for ci environments with a cpu quota; 0 leaves rayon's
+    // default (one thread per core) in place
+    // --fix --interactive prompts on stdin per fixable diagnostic, which
+    // only makes sense one file at a time; force a single worker so
+    // prompts from different files can't interleave on the terminal.
+    // --debug has the same hazard for its multi-line token/ast dumps:
+    // two workers printing their dumps at once would interleave into
+    // unreadable output, so it also gets a single worker
+    let thread_count = if (cli.fix && cli.interactive) || cli.debug { 1 } else { cli.threads };
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(thread_count)
+        .build()
+        .expect("failed to build thread pool");
+    let results: Vec<FileResult> =
+        pool.install(|| files.par_iter().map(|file| lint_file(file, &cli)).collect());
+
+    let had_fatal = results.iter().any(|r| r.fatal);
+    let had_errors = results.iter().any(|r| !r.errors.is_empty());
+
+    if let Some(patch_path) = &cli.emit_patch {
+        let patch: String = results.iter().filter_map(|r| r.patch.as_deref()).collect();
+        if let Err(e) = fs::write(patch_path, &patch) {
+            eprintln!(
+                "{} Could not write patch to '{}': {}",
+                "error:".red().bold(),
+                patch_path,
+                e
+            );
+        } else if !cli.json {
+            println!("{} wrote patch to {}", "fix:".green().bold(), patch_path);
+        }
+    }
+
+    render_results(&cli, results);
+
+    // exit with appropriate code: 2 for a file that couldn't be read/parsed,
+    // 1 for lint errors, 0 for success
+    if had_fatal {
+        process::exit(2);
+    }
+    if had_errors {
+        process::exit(1);
+    }
+}
+
+/// renders linting results in whichever format the caller asked for; this
+/// is the single place that decides between the human, json, and ci/editor
+/// integration output formats
+fn render_results(cli: &Cli, results: Vec<FileResult>) {
+    // --group-by/--sort-by only reshape the human-readable report; ci and
+    // editor integration formats already carry their own structure
+    let is_human_format = matches!(cli.format, None | Some(OutputFormat::Full)) && !cli.json;
+    if is_human_format && (cli.group_by.is_some() || cli.sort_by.is_some()) {
+        print_organized(
+            &results,
+            cli.group_by.unwrap_or(GroupBy::File),
+            cli.sort_by.unwrap_or(SortBy::Location),
+        );
+        if cli.rule_summary {
+            print_rule_summary(&summarize_rules(&results));
+        }
+        return;
+    }
+
+    match cli.format {
+        Some(OutputFormat::Github) => {
+            for result in &results {
+                print_github_annotations(result);
+            }
+        }
+        Some(OutputFormat::Gitlab) => print_gitlab_report(&results),
+        Some(OutputFormat::Rdjson) => print_rdjson_report(&results),
+        Some(OutputFormat::Quickfix) => {
+            for result in &results {
+                print_quickfix(result);
+            }
+        }
+        Some(OutputFormat::Compact) => {
+            for result in &results {
+                print_compact(result);
+            }
+        }
+        Some(OutputFormat::Full) => print_full(&results),
+        Some(OutputFormat::Rustc) => {
+            for result in &results {
+                print_rustc_style(result);
+            }
+        }
+        Some(OutputFormat::Jsonl) => {
+            for result in &results {
+                print_jsonl(result);
+            }
+        }
+        Some(OutputFormat::Plain) => print_plain_full(&results),
+        None if cli.json => {
+            print_json(results, cli.rule_summary);
+            return;
+        }
+        None => print_full(&results),
+    }
+
+    if cli.rule_summary {
+        print_rule_summary(&summarize_rules(&results));
+    }
+}
+
+/// prints results as a versioned json report
+fn print_json(results: Vec<FileResult>, rule_summary: bool) {
+    let rule_summary = rule_summary.then(|| summarize_rules(&results));
+
+    let files: Vec<JsonOutput> = results
+        .into_iter()
+        .map(|r| JsonOutput {
+            file: r.file,
+            errors: r.errors.into_iter().map(|d| d.message).collect(),
+            warnings: r.warnings.into_iter().map(|d| d.message).collect(),
+            errors_truncated: r.errors_truncated,
+            warnings_truncated: r.warnings_truncated,
+            stats: r.stats,
+            suggestions: r.suggestions,
+        })
+        .collect();
+
+    let report = JsonReport {
+        schema_version: JSON_SCHEMA_VERSION,
+        files,
+        rule_summary,
+    };
+
+    println!("{}", serde_json::to_string_pretty(&report).unwrap());
+}
+
+/// implements `--emit tokens`/`--emit ast`/`--emit symbols`: lexes (and, for
+/// `ast`/`symbols`, parses) each file and prints its structure as one json
+/// object per line, instead of running the linter at all. returns the
+/// process exit code: 2 if any file couldn't be read or (for `ast`/
+/// `symbols`) failed to parse, 0 otherwise
+fn run_emit(kind: EmitKind, files: &[String]) -> i32 {
+    let mut had_error = false;
+
+    for file in files {
+        let content = match fs::read_to_string(file) {
+            Ok(c) => c,
+            Err(e) => {
+                eprintln!("error: could not read '{}': {}", file, e);
+                had_error = true;
+                continue;
+            }
+        };
+
+        let mut lexer = Lexer::new(content);
+        let tokens = lexer.tokenize();
+
+        match kind {
+            EmitKind::Tokens => {
+                #[derive(Serialize)]
+                struct Dump<'a> {
+                    file: &'a str,
+                    tokens: &'a [lol_lint::types::Token],
+                }
+                println!(
+                    "{}",
+                    serde_json::to_string(&Dump { file, tokens: &tokens }).unwrap()
+                );
+            }
+            EmitKind::Ast => {
+                let mut parser = Parser::new(tokens);
+                let program = parser.parse_program();
+                if !parser.errors.is_empty() {
+                    eprintln!("error: parsing failed in '{}'", file);
+                    had_error = true;
+                }
+                #[derive(Serialize)]
+                struct Dump<'a> {
+                    file: &'a str,
+                    ast: &'a ast::Program,
+                }
+                println!(
+                    "{}",
+                    serde_json::to_string(&Dump { file, ast: &program }).unwrap()
+                );
+            }
+            EmitKind::Symbols => {
+                let mut parser = Parser::new(tokens);
+                let program = parser.parse_program();
+                if !parser.errors.is_empty() {
+                    eprintln!("error: parsing failed in '{}'", file);
+                    had_error = true;
+                }
+                let table = lol_lint::symbols::SymbolTable::build(&program);
+
+                // reshaped for editor outline views and dead-code tooling:
+                // one entry per symbol with its declaration site split out
+                // from its later references, rather than the library's flat
+                // chronological list of every occurrence
+                #[derive(Serialize)]
+                struct SymbolEntry<'a> {
+                    name: &'a str,
+                    kind: &'static str,
+                    declared_at: Option<&'a ast::Position>,
+                    references: Vec<&'a ast::Position>,
+                }
+                #[derive(Serialize)]
+                struct Dump<'a> {
+                    file: &'a str,
+                    symbols: Vec<SymbolEntry<'a>>,
+                }
+
+                let symbols = table
+                    .symbols()
+                    .iter()
+                    .map(|symbol| SymbolEntry {
+                        name: &symbol.name,
+                        kind: "variable",
+                        declared_at: symbol.definition(),
+                        references: symbol
+                            .uses
+                            .iter()
+                            .filter(|u| u.kind != lol_lint::symbols::Occurrence::Definition)
+                            .map(|u| &u.pos)
+                            .collect(),
+                    })
+                    .collect();
+
+                println!("{}", serde_json::to_string(&Dump { file, symbols }).unwrap());
+            }
+            EmitKind::Callgraph => {
+                // this parser's ast has no function representation at all
+                // -- `HOW DUZ`/`FOUND YR`/`I IZ` aren't parsed (see
+                // cfg.rs's doc comment) -- so there is nothing for a call
+                // graph to draw yet: no nodes, no unreachable functions, no
+                // recursion cycles. printing an empty-but-valid dot graph
+                // (rather than skipping `--emit callgraph` entirely) keeps
+                // it wired into any pipeline expecting dot output today,
+                // ready to gain real nodes if this ast ever grows functions
+                println!("digraph callgraph {{");
+                println!("  // {}: no functions in this ast to graph", file);
+                println!("}}");
+            }
+            EmitKind::Depgraph => {
+                let mut parser = Parser::new(tokens);
+                let program = parser.parse_program();
+                if !parser.errors.is_empty() {
+                    eprintln!("error: parsing failed in '{}'", file);
+                    had_error = true;
+                }
+
+                let mut nodes = std::collections::BTreeSet::new();
+                let mut edges = Vec::new();
+                collect_dependency_edges(&program.body, &mut nodes, &mut edges);
+
+                println!("digraph depgraph {{");
+                for node in &nodes {
+                    println!("  {:?};", node);
+                }
+                for (from, to) in &edges {
+                    println!("  {:?} -> {:?};", from, to);
+                }
+                println!("}}");
+            }
+            EmitKind::AstDot => {
+                let mut parser = Parser::new(tokens);
+                let program = parser.parse_program();
+                if !parser.errors.is_empty() {
+                    eprintln!("error: parsing failed in '{}'", file);
+                    had_error = true;
+                }
+
+                let mut out = String::from("digraph ast {\n");
+                let mut next_id = 0;
+                let root = ast_dot_node(&mut out, &mut next_id, "Program", &program.version);
+                for stmt in &program.body.statements {
+                    let child = ast_dot_statement(&mut out, &mut next_id, stmt);
+                    out.push_str(&format!("  n{} -> n{};\n", root, child));
+                }
+                out.push_str("}\n");
+                print!("{out}");
+            }
+            EmitKind::CfgDot => {
+                let mut parser = Parser::new(tokens);
+                let program = parser.parse_program();
+                if !parser.errors.is_empty() {
+                    eprintln!("error: parsing failed in '{}'", file);
+                    had_error = true;
+                }
+
+                let cfg = lol_lint::cfg::Cfg::build(&program);
+                println!("digraph cfg {{");
+                for block in &cfg.blocks {
+                    let kinds: Vec<&str> = block.statements.iter().map(|s| statement_kind(s)).collect();
+                    let label = if kinds.is_empty() { "(empty)".to_string() } else { kinds.join("; ") };
+                    let shape = if cfg.is_unreachable(block.id) { "diamond" } else { "box" };
+                    println!("  b{} [shape={}, label={:?}];", block.id, shape, format!("bb{}\n{label}", block.id));
+
+                    // `cfg::BasicBlock` doesn't record *why* an edge exists,
+                    // only that it does, so a branch label here is inferred
+                    // from the builder's own edge order rather than read
+                    // off the graph directly: the only place a block gets
+                    // two successors is an `O RLY?`, added ya-rly-then-
+                    // no-wai, so the first is labeled the taken branch and
+                    // the second the untaken one. a loop's back-edge has no
+                    // such tell -- structurally it's a block jumping to an
+                    // earlier id, but so is the ordinary forward merge
+                    // after every `O RLY?` with no `NO WAI` block of its
+                    // own to distinguish it, since `after` is always
+                    // allocated before `then`/`else` -- so back-edges are
+                    // left unlabeled rather than guessed and risk being
+                    // wrong more often than right
+                    for (i, &succ) in block.successors.iter().enumerate() {
+                        if block.successors.len() == 2 {
+                            let label = if i == 0 { "YA RLY" } else { "NO WAI" };
+                            println!("  b{} -> b{} [label={:?}];", block.id, succ, label);
+                        } else {
+                            println!("  b{} -> b{};", block.id, succ);
+                        }
+                    }
+                }
+                println!("}}");
+            }
+        }
+    }
+
+    if had_error {
+        2
+    } else {
+        0
+    }
+}
+
+/// allocates a fresh node id and emits `n{id} [label="{label}\n{detail}"];`,
+/// returning the id so the caller can wire it to its parent
+fn ast_dot_node(out: &mut String, next_id: &mut usize, label: &str, detail: &str) -> usize {
+    let id = *next_id;
+    *next_id += 1;
+    out.push_str(&format!("  n{} [label={:?}];\n", id, format!("{label}\n{detail}")));
+    id
+}
+
+fn ast_dot_pos(pos: &ast::Position) -> String {
+    format!("{}:{}", pos.line, pos.column)
+}
+
+fn ast_dot_statement(out: &mut String, next_id: &mut usize, stmt: &ast::Statement) -> usize {
+    match stmt {
+        ast::Statement::Declaration { name, value, pos } => {
+            let id = ast_dot_node(out, next_id, "Declaration", &format!("{name}\n{}", ast_dot_pos(pos)));
+            if let Some(value) = value {
+                let child = ast_dot_expression(out, next_id, value);
+                out.push_str(&format!("  n{} -> n{};\n", id, child));
+            }
+            id
+        }
+        ast::Statement::Assignment { name, value, pos } => {
+            let id = ast_dot_node(out, next_id, "Assignment", &format!("{name}\n{}", ast_dot_pos(pos)));
+            if let Some(value) = value {
+                let child = ast_dot_expression(out, next_id, value);
+                out.push_str(&format!("  n{} -> n{};\n", id, child));
+            }
+            id
+        }
+        ast::Statement::Visible { expressions, pos } => {
+            let id = ast_dot_node(out, next_id, "Visible", &ast_dot_pos(pos));
+            for expr in expressions {
+                let child = ast_dot_expression(out, next_id, expr);
+                out.push_str(&format!("  n{} -> n{};\n", id, child));
+            }
+            id
+        }
+        ast::Statement::ORly { ya_rly, no_wai, pos, .. } => {
+            let id = ast_dot_node(out, next_id, "ORly", &ast_dot_pos(pos));
+            for stmt in &ya_rly.statements {
+                let child = ast_dot_statement(out, next_id, stmt);
+                out.push_str(&format!("  n{} -> n{} [label=\"YA RLY\"];\n", id, child));
+            }
+            if let Some(no_wai) = no_wai {
+                for stmt in &no_wai.statements {
+                    let child = ast_dot_statement(out, next_id, stmt);
+                    out.push_str(&format!("  n{} -> n{} [label=\"NO WAI\"];\n", id, child));
+                }
+            }
+            id
+        }
+        ast::Statement::Loop { body, pos } => {
+            let id = ast_dot_node(out, next_id, "Loop", &ast_dot_pos(pos));
+            for stmt in &body.statements {
+                let child = ast_dot_statement(out, next_id, stmt);
+                out.push_str(&format!("  n{} -> n{};\n", id, child));
+            }
+            id
+        }
+        ast::Statement::Gtfo { pos } => ast_dot_node(out, next_id, "Gtfo", &ast_dot_pos(pos)),
+        ast::Statement::Gimmeh { name, pos } => {
+            ast_dot_node(out, next_id, "Gimmeh", &format!("{name}\n{}", ast_dot_pos(pos)))
+        }
+        ast::Statement::Expr { expression, .. } => {
+            let id = ast_dot_node(out, next_id, "Expr", &ast_dot_pos(expression.position()));
+            let child = ast_dot_expression(out, next_id, expression);
+            out.push_str(&format!("  n{} -> n{};\n", id, child));
+            id
+        }
+    }
+}
+
+fn ast_dot_expression(out: &mut String, next_id: &mut usize, expr: &ast::Expression) -> usize {
+    match expr {
+        ast::Expression::Number(n, pos) => ast_dot_node(out, next_id, "Number", &format!("{n}\n{}", ast_dot_pos(pos))),
+        ast::Expression::String(s, pos) => ast_dot_node(out, next_id, "String", &format!("{s}\n{}", ast_dot_pos(pos))),
+        ast::Expression::Identifier(name, pos) => {
+            ast_dot_node(out, next_id, "Identifier", &format!("{name}\n{}", ast_dot_pos(pos)))
+        }
+        ast::Expression::Sum { left, right, pos }
+        | ast::Expression::Diff { left, right, pos }
+        | ast::Expression::Produkt { left, right, pos }
+        | ast::Expression::Quoshunt { left, right, pos }
+        | ast::Expression::Mod { left, right, pos }
+        | ast::Expression::BothSaem { left, right, pos }
+        | ast::Expression::Diffrint { left, right, pos } => {
+            let id = ast_dot_node(out, next_id, expr_variant_name(expr), &ast_dot_pos(pos));
+            let left_id = ast_dot_expression(out, next_id, left);
+            let right_id = ast_dot_expression(out, next_id, right);
+            out.push_str(&format!("  n{} -> n{};\n", id, left_id));
+            out.push_str(&format!("  n{} -> n{};\n", id, right_id));
+            id
+        }
+    }
+}
+
+fn statement_kind(stmt: &ast::Statement) -> &'static str {
+    match stmt {
+        ast::Statement::Declaration { .. } => "Declaration",
+        ast::Statement::Assignment { .. } => "Assignment",
+        ast::Statement::Visible { .. } => "Visible",
+        ast::Statement::ORly { .. } => "ORly",
+        ast::Statement::Loop { .. } => "Loop",
+        ast::Statement::Gtfo { .. } => "Gtfo",
+        ast::Statement::Gimmeh { .. } => "Gimmeh",
+        ast::Statement::Expr { .. } => "Expr",
+    }
+}
+
+fn expr_variant_name(expr: &ast::Expression) -> &'static str {
+    match expr {
+        ast::Expression::Number(..) => "Number",
+        ast::Expression::String(..) => "String",
+        ast::Expression::Identifier(..) => "Identifier",
+        ast::Expression::Sum { .. } => "Sum",
+        ast::Expression::Diff { .. } => "Diff",
+        ast::Expression::Produkt { .. } => "Produkt",
+        ast::Expression::Quoshunt { .. } => "Quoshunt",
+        ast::Expression::Mod { .. } => "Mod",
+        ast::Expression::BothSaem { .. } => "BothSaem",
+        ast::Expression::Diffrint { .. } => "Diffrint",
+    }
+}
+
+/// walks `block` (recursing into `O RLY?`/`IM IN YR LOOP` bodies) collecting
+/// every variable name touched, and an edge `dep -> target` for each
+/// variable `dep` read while computing the value stored into `target` by a
+/// declaration or assignment. `GIMMEH` and a bare `I HAS A` with no
+/// initializer add `target` to `nodes` with no incoming edges, since
+/// nothing here feeds them
+fn collect_dependency_edges(
+    block: &ast::Block,
+    nodes: &mut std::collections::BTreeSet<String>,
+    edges: &mut Vec<(String, String)>,
+) {
+    for stmt in &block.statements {
+        match stmt {
+            ast::Statement::Declaration { name, value, .. } | ast::Statement::Assignment { name, value, .. } => {
+                nodes.insert(name.clone());
+                if let Some(value) = value {
+                    let mut deps = std::collections::BTreeSet::new();
+                    collect_identifiers(value, &mut deps);
+                    for dep in deps {
+                        nodes.insert(dep.clone());
+                        edges.push((dep, name.clone()));
+                    }
+                }
+            }
+            ast::Statement::Gimmeh { name, .. } => {
+                nodes.insert(name.clone());
+            }
+            ast::Statement::Visible { expressions, .. } => {
+                for expr in expressions {
+                    let mut deps = std::collections::BTreeSet::new();
+                    collect_identifiers(expr, &mut deps);
+                    nodes.extend(deps);
+                }
+            }
+            ast::Statement::ORly { ya_rly, no_wai, .. } => {
+                collect_dependency_edges(ya_rly, nodes, edges);
+                if let Some(no_wai) = no_wai {
+                    collect_dependency_edges(no_wai, nodes, edges);
+                }
+            }
+            ast::Statement::Loop { body, .. } => collect_dependency_edges(body, nodes, edges),
+            ast::Statement::Gtfo { .. } | ast::Statement::Expr { .. } => {}
+        }
+    }
+}
+
+/// every `Identifier` reachable from `expr`, recursing through the binary
+/// operators
+fn collect_identifiers(expr: &ast::Expression, out: &mut std::collections::BTreeSet<String>) {
+    match expr {
+        ast::Expression::Identifier(name, _) => {
+            out.insert(name.clone());
+        }
+        ast::Expression::Number(..) | ast::Expression::String(..) => {}
+        ast::Expression::Sum { left, right, .. }
+        | ast::Expression::Diff { left, right, .. }
+        | ast::Expression::Produkt { left, right, .. }
+        | ast::Expression::Quoshunt { left, right, .. }
+        | ast::Expression::Mod { left, right, .. }
+        | ast::Expression::BothSaem { left, right, .. }
+        | ast::Expression::Diffrint { left, right, .. } => {
+            collect_identifiers(left, out);
+            collect_identifiers(right, out);
+        }
+    }
+}
+
+/// prints the json schema describing the `--json` report shape, so
+/// integrators can validate against it instead of guessing the fields
+fn print_output_schema() {
+    let schema = serde_json::json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": "lol-lint json report",
+        "type": "object",
+        "properties": {
+            "schema_version": { "type": "integer", "const": JSON_SCHEMA_VERSION },
+            "files": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "file": { "type": "string" },
+                        "errors": { "type": "array", "items": { "type": "string" } },
+                        "warnings": { "type": "array", "items": { "type": "string" } },
+                        "errors_truncated": { "type": "integer" },
+                        "warnings_truncated": { "type": "integer" },
+                        "stats": {
+                            "type": ["object", "null"],
+                            "properties": {
+                                "lines_of_code": { "type": "integer" },
+                                "variables": { "type": "integer" },
+                                "loops": { "type": "integer" },
+                                "conditionals": { "type": "integer" },
+                                "expressions": { "type": "integer" }
+                            }
+                        },
+                        "suggestions": {
+                            "type": "array",
+                            "items": {
+                                "type": "object",
+                                "properties": {
+                                    "message": { "type": "string" },
+                                    "start_byte": { "type": "integer" },
+                                    "end_byte": { "type": "integer" },
+                                    "replacement": { "type": "string" },
+                                    "applicability": { "type": "string" }
+                                },
+                                "required": ["message", "start_byte", "end_byte", "replacement", "applicability"]
+                            }
+                        }
+                    },
+                    "required": ["file", "errors", "warnings", "suggestions"]
+                }
+            },
+            "rule_summary": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "code": { "type": "string" },
+                        "count": { "type": "integer" }
+                    },
+                    "required": ["code", "count"]
+                }
+            }
+        },
+        "required": ["schema_version", "files"]
+    });
+
+    println!("{}", serde_json::to_string_pretty(&schema).unwrap());
+}
+
+/// prints every known rule's code, name, default severity, fixability, and
+/// a one-line summary, as a table or (with `--json`) a json array so
+/// editors can build their own rule-list ui without scraping source
+fn print_rule_list(as_json: bool) {
+    let all_rules = rules::all();
+
+    if as_json {
+        println!("{}", serde_json::to_string_pretty(&all_rules).unwrap());
+        return;
+    }
+
+    for rule in &all_rules {
+        let fixable = if rule.fixable { "fixable" } else { "-" };
+        println!(
+            "{:<7} {:<28} {:<8} {:<8} {}",
+            rule.code, rule.name, rule.severity_str, fixable, rule.summary
+        );
+    }
+}
+
+/// a single diagnostic flattened out of a `FileResult`, tagged with the
+/// file it came from and its resolved position/rule for grouping and
+/// sorting purposes
+struct DiagEntry<'a> {
+    file: &'a str,
+    severity: &'static str,
+    message: &'a str,
+    rule: Option<&'static str>,
+    line: usize,
+    column: usize,
+}
+
+fn severity_rank(severity: &str) -> u8 {
+    if severity == "error" {
+        0
+    } else {
+        1
+    }
+}
+
+/// flattens every error and warning across all results into `DiagEntry`s.
+/// a diagnostic's line comes straight from its own span, so a
+/// line-only diagnostic (e.g. LL020's unused suppression comment, which has
+/// no column) still sorts and groups by its real line instead of falling
+/// back to the end of the list -- only the column, which such a diagnostic
+/// genuinely lacks, falls back to `usize::MAX`
+fn collect_diag_entries(results: &[FileResult]) -> Vec<DiagEntry<'_>> {
+    let mut entries = Vec::new();
+
+    for result in results {
+        for diagnostic in &result.errors {
+            entries.push(DiagEntry {
+                file: &result.file,
+                severity: "error",
+                message: &diagnostic.message,
+                rule: diagnostic.code,
+                line: diagnostic.span.map(|s| s.line).unwrap_or(usize::MAX),
+                column: diagnostic.span.and_then(|s| s.column).unwrap_or(usize::MAX),
+            });
+        }
+        for diagnostic in &result.warnings {
+            entries.push(DiagEntry {
+                file: &result.file,
+                severity: "warning",
+                message: &diagnostic.message,
+                rule: diagnostic.code,
+                line: diagnostic.span.map(|s| s.line).unwrap_or(usize::MAX),
+                column: diagnostic.span.and_then(|s| s.column).unwrap_or(usize::MAX),
+            });
+        }
+    }
+
+    entries
+}
+
+/// orders two entries first by group (so same-group entries land together),
+/// then by the requested sort key
+fn compare_entries(a: &DiagEntry, b: &DiagEntry, group_by: GroupBy, sort_by: SortBy) -> std::cmp::Ordering {
+    let group_order = match group_by {
+        GroupBy::File => a.file.cmp(b.file),
+        GroupBy::Rule => a.rule.unwrap_or("").cmp(b.rule.unwrap_or("")),
+        GroupBy::Severity => severity_rank(a.severity).cmp(&severity_rank(b.severity)),
+    };
+    if group_order != std::cmp::Ordering::Equal {
+        return group_order;
+    }
+
+    match sort_by {
+        SortBy::Location => (a.line, a.column).cmp(&(b.line, b.column)),
+        SortBy::Severity => severity_rank(a.severity)
+            .cmp(&severity_rank(b.severity))
+            .then((a.line, a.column).cmp(&(b.line, b.column))),
+        SortBy::Rule => a
+            .rule
+            .unwrap_or("")
+            .cmp(b.rule.unwrap_or(""))
+            .then((a.line, a.column).cmp(&(b.line, b.column))),
+    }
+}
+
+/// the header printed above each group of diagnostics
+fn group_label(entry: &DiagEntry, group_by: GroupBy) -> String {
+    match group_by {
+        GroupBy::File => entry.file.to_string(),
+        GroupBy::Rule => entry.rule.unwrap_or("unclassified").to_string(),
+        GroupBy::Severity => {
+            if entry.severity == "error" {
+                "errors".to_string()
+            } else {
+                "warnings".to_string()
+            }
+        }
+    }
+}
+
+/// prints diagnostics grouped and sorted per `--group-by`/`--sort-by`,
+/// replacing the default flat per-file errors-then-warnings dump
+fn print_organized(results: &[FileResult], group_by: GroupBy, sort_by: SortBy) {
+    let mut entries = collect_diag_entries(results);
+    entries.sort_by(|a, b| compare_entries(a, b, group_by, sort_by));
+
+    if entries.is_empty() {
+        println!("{} No linting issues found", "✓".green().bold());
+        return;
+    }
+
+    let mut current_group: Option<String> = None;
+    for entry in &entries {
+        let label = group_label(entry, group_by);
+        if current_group.as_deref() != Some(label.as_str()) {
+            if current_group.is_some() {
+                println!();
+            }
+            println!("{}", format!("== {} ==", label).cyan().bold());
+            current_group = Some(label);
+        }
+
+        // grouping by file already scopes each line to one file, so the
+        // file name would be redundant; other groupings interleave files
+        let line = if group_by == GroupBy::File {
+            entry.message.to_string()
+        } else {
+            format!("{}: {}", entry.file, entry.message)
+        };
+        println!(
+            "{}",
+            if entry.severity == "error" {
+                line.red()
+            } else {
+                line.yellow()
+            }
+        );
+    }
+}
+
+/// prints the default multi-line human-readable report, with a `== file ==`
+/// header per file once there is more than one
+fn print_full(results: &[FileResult]) {
+    let show_file_headers = results.len() > 1;
+    for result in results {
+        if show_file_headers {
+            println!("{}", format!("== {} ==", result.file).cyan().bold());
+        }
+        print_human_readable(result);
+    }
+}
+
+/// prints the accessible plain-text report, with a `File: ...` heading per
+/// file once there is more than one; never emits color, box drawing,
+/// carets, or glyphs like `✓`, and spells out counts and positions in words
+fn print_plain_full(results: &[FileResult]) {
+    let show_file_headers = results.len() > 1;
+    for result in results {
+        if show_file_headers {
+            println!("File: {}", result.file);
+        }
+        print_plain(result);
+    }
+}
+
+/// prints one file's diagnostics in plain, screen-reader-friendly text
+fn print_plain(result: &FileResult) {
+    for error in &result.errors {
+        println!("{}", error.message);
+    }
+    if result.errors_truncated > 0 {
+        println!(
+            "... and {} more error{}",
+            result.errors_truncated,
+            if result.errors_truncated == 1 { "" } else { "s" }
+        );
+    }
+
+    for warning in &result.warnings {
+        println!("{}", warning.message);
+    }
+    if result.warnings_truncated > 0 {
+        println!(
+            "... and {} more warning{}",
+            result.warnings_truncated,
+            if result.warnings_truncated == 1 { "" } else { "s" }
+        );
+    }
+
+    let error_count = result.errors.len() + result.errors_truncated;
+    let warning_count = result.warning_total;
+
+    if error_count > 0 || warning_count > 0 {
+        println!();
+        println!(
+            "{} error{}, {} warning{}",
+            error_count,
+            if error_count == 1 { "" } else { "s" },
+            warning_count,
+            if warning_count == 1 { "" } else { "s" }
+        );
+    } else {
+        println!("No linting issues found.");
+    }
+
+    if let Some(s) = &result.stats {
+        println!();
+        println!("Statistics:");
+        println!("Lines of code: {}", s.lines_of_code);
+        println!("Variables: {}", s.variables);
+        println!("Loops: {}", s.loops);
+        println!("Conditionals: {}", s.conditionals);
+        println!("Expressions: {}", s.expressions);
+    }
+}
+
+/// prints one colored line per diagnostic with no headers, summary, or
+/// statistics, for scanning many files at a glance
+fn print_compact(result: &FileResult) {
+    for error in &result.errors {
+        println!("{}", quickfix_line(&result.file, "error", error).red());
+    }
+    if result.errors_truncated > 0 {
+        println!(
+            "{}",
+            format!("{}: ... and {} more errors", result.file, result.errors_truncated).red()
+        );
+    }
+    for warning in &result.warnings {
+        println!(
+            "{}",
+            quickfix_line(&result.file, "warning", warning).yellow()
+        );
+    }
+    if result.warnings_truncated > 0 {
+        println!(
+            "{}",
+            format!(
+                "{}: ... and {} more warnings",
+                result.file, result.warnings_truncated
+            )
+            .yellow()
+        );
+    }
+}
+
+/// prints each diagnostic rustc-style: the message, an arrow to the
+/// location, and the offending source line with a caret under the column
+fn print_rustc_style(result: &FileResult) {
+    let source = fs::read_to_string(&result.file).ok();
+    let lines: Vec<&str> = source.as_deref().map(|s| s.lines().collect()).unwrap_or_default();
+
+    for error in &result.errors {
+        print_rustc_snippet(&result.file, "error", error, &lines);
+    }
+    for warning in &result.warnings {
+        print_rustc_snippet(&result.file, "warning", warning, &lines);
+    }
+}
+
+/// prints one rustc-style snippet, falling back to a bare message when the
+/// diagnostic carries no position or the source line isn't available
+fn print_rustc_snippet(file: &str, severity: &str, diagnostic: &Diagnostic, lines: &[&str]) {
+    let severity_label = if severity == "error" {
+        "error:".red().bold()
+    } else {
+        "warning:".yellow().bold()
+    };
+    println!("{} {}", severity_label, diagnostic.message);
+
+    if let Some(span) = diagnostic.span {
+        if let Some(col) = span.column {
+            println!("  {} {}:{}:{}", "-->".blue().bold(), file, span.line, col);
+            if let Some(&text) = lines.get(span.line.saturating_sub(1)) {
+                let gutter = span.line.to_string();
+                println!("{} {}", " ".repeat(gutter.len()), "|".blue().bold());
+                println!("{} {} {}", gutter.blue().bold(), "|".blue().bold(), text);
+                let caret = " ".repeat(col.saturating_sub(1)) + "^";
+                println!(
+                    "{} {} {}",
+                    " ".repeat(gutter.len()),
+                    "|".blue().bold(),
+                    caret.red().bold()
+                );
+            }
+        }
+    }
+    println!();
+}
+
+/// prints one json object per diagnostic in a single file's result
+fn print_jsonl(result: &FileResult) {
+    for error in &result.errors {
+        println!(
+            "{}",
+            serde_json::to_string(&JsonlDiagnostic {
+                file: &result.file,
+                severity: "error",
+                message: &error.message,
+            })
+            .unwrap()
+        );
+    }
+    for warning in &result.warnings {
+        println!(
+            "{}",
+            serde_json::to_string(&JsonlDiagnostic {
+                file: &result.file,
+                severity: "warning",
+                message: &warning.message,
+            })
+            .unwrap()
+        );
+    }
+}
+
+/// expands glob patterns and directories in the given arguments into
+/// concrete file paths; an argument that isn't a glob (or matches nothing)
+/// is passed through unchanged so the usual "could not read file" error
+/// still applies to it, and any directory is walked recursively for `.lol`
+/// files
+fn resolve_files(patterns: &[String], ignore_patterns: &[String]) -> Vec<String> {
+    let mut files = Vec::new();
 
-    /// output results as json for ci/cd integration
-    #[arg(long)]
-    json: bool,
+    for pattern in patterns {
+        let matches: Vec<String> = glob::glob(pattern)
+            .into_iter()
+            .flatten()
+            .filter_map(Result::ok)
+            .map(|p| p.to_string_lossy().into_owned())
+            .collect();
 
-    /// show code statistics (loc, variables, loops, etc.)
-    #[arg(long)]
-    stats: bool,
+        let candidates = if matches.is_empty() {
+            vec![pattern.clone()]
+        } else {
+            matches
+        };
 
-    /// disable colored output for terminal compatibility
-    #[arg(long)]
-    no_color: bool,
+        for candidate in candidates {
+            if ignore::is_ignored(&candidate, ignore_patterns) {
+                continue;
+            }
+            let path = std::path::Path::new(&candidate);
+            if path.is_dir() {
+                collect_lol_files(path, ignore_patterns, &mut files);
+            } else {
+                files.push(candidate);
+            }
+        }
+    }
 
-    /// show debug information including tokens and ast
-    #[arg(long)]
-    debug: bool,
+    files
 }
 
-/// json output format for machine-readable results
-#[derive(Serialize)]
-struct JsonOutput {
-    file: String,
-    errors: Vec<String>,
-    warnings: Vec<String>,
-    stats: Option<Stats>,
+/// recursively collects `.lol` files under a directory, in sorted order,
+/// skipping anything matched by `ignore_patterns`
+fn collect_lol_files(dir: &std::path::Path, ignore_patterns: &[String], out: &mut Vec<String>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    let mut entries: Vec<_> = entries.flatten().map(|e| e.path()).collect();
+    entries.sort();
+
+    for path in entries {
+        let path_str = path.to_string_lossy();
+        if ignore::is_ignored(&path_str, ignore_patterns) {
+            continue;
+        }
+        if path.is_dir() {
+            collect_lol_files(&path, ignore_patterns, out);
+        } else if path.extension().and_then(|e| e.to_str()) == Some("lol") {
+            out.push(path_str.into_owned());
+        }
+    }
 }
 
-/// code statistics collected during ast traversal
-#[derive(Serialize)]
-struct Stats {
-    lines_of_code: usize,
-    variables: usize,
-    loops: usize,
-    conditionals: usize,
-    expressions: usize,
+/// runs the full lex/parse/lint pipeline on a single file, or on standard
+/// input when `file` is `-`
+/// prints a unified-diff-style preview of what `--fix` would change in
+/// `file`; since every fix lol-lint currently makes replaces a line in
+/// place (trimming trailing whitespace) or appends one (a final newline)
+/// without ever changing the line count, a plain by-index comparison is
+/// enough — no general diff algorithm is needed
+fn print_fix_diff(file: &str, original: &str, fixed: &str) {
+    println!("{}", format!("--- a/{}", file).bold());
+    println!("{}", format!("+++ b/{}", file).bold());
+
+    let before: Vec<&str> = original.lines().collect();
+    let after: Vec<&str> = fixed.lines().collect();
+
+    for i in 0..before.len().max(after.len()) {
+        let old = before.get(i).copied();
+        let new = after.get(i).copied();
+        if old == new {
+            continue;
+        }
+        let line_no = i + 1;
+        println!("{}", format!("@@ -{line_no} +{line_no} @@").cyan());
+        if let Some(old) = old {
+            println!("{}", format!("-{old}").red());
+        }
+        if let Some(new) = new {
+            println!("{}", format!("+{new}").green());
+        }
+    }
 }
 
-fn main() {
-    let cli = Cli::parse();
-
-    // disable colored output if requested for terminal compatibility
-    if cli.no_color {
-        colored::control::set_override(false);
-    }
-
-    // read source file into memory
-    let content = match fs::read_to_string(&cli.file) {
-        Ok(c) => c,
-        Err(e) => {
-            if !cli.json {
-                eprintln!(
-                    "{} Could not read file '{}': {}",
-                    "error:".red().bold(),
-                    cli.file,
-                    e
-                );
+/// one line's fate between two versions of a file, as produced by
+/// `lcs_diff`
+enum DiffOp<'a> {
+    Equal(&'a str),
+    Delete(&'a str),
+    Insert(&'a str),
+}
+
+/// a minimal longest-common-subsequence line diff: fine for the small
+/// lolcode files this tool targets, without pulling in a diff crate for
+/// the one place that needs a real (not by-index) comparison
+fn lcs_diff<'a>(a: &[&'a str], b: &[&'a str]) -> Vec<DiffOp<'a>> {
+    let (n, m) = (a.len(), b.len());
+    let mut dp = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            dp[i][j] = if a[i] == b[j] {
+                dp[i + 1][j + 1] + 1
+            } else {
+                dp[i + 1][j].max(dp[i][j + 1])
+            };
+        }
+    }
+
+    let mut ops = Vec::with_capacity(n + m);
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if a[i] == b[j] {
+            ops.push(DiffOp::Equal(a[i]));
+            i += 1;
+            j += 1;
+        } else if dp[i + 1][j] >= dp[i][j + 1] {
+            ops.push(DiffOp::Delete(a[i]));
+            i += 1;
+        } else {
+            ops.push(DiffOp::Insert(b[j]));
+            j += 1;
+        }
+    }
+    ops.extend(a[i..].iter().map(|l| DiffOp::Delete(l)));
+    ops.extend(b[j..].iter().map(|l| DiffOp::Insert(l)));
+    ops
+}
+
+/// lines of unchanged context kept around each hunk, matching the default
+/// `diff -u`/`git diff` context so `git apply` accepts the patch without
+/// needing `--unidiff-zero`
+const DIFF_CONTEXT: usize = 3;
+
+/// renders a standard unified diff between `original` and `fixed`, for
+/// `--emit-patch` to write out a `git apply`-compatible patch; unlike
+/// `print_fix_diff`'s by-index comparison this uses a real line diff, so
+/// it stays correct for fixes that insert or delete lines (e.g. LL009's
+/// NO WAI insertion) rather than only ones that replace a line in place
+fn unified_diff(file: &str, original: &str, fixed: &str) -> String {
+    let before: Vec<&str> = original.lines().collect();
+    let after: Vec<&str> = fixed.lines().collect();
+    let ops = lcs_diff(&before, &after);
+
+    // 1-based old/new line number at the start of each op, so a hunk's
+    // header can be computed from any slice of `ops` without re-walking
+    // everything before it
+    let mut old_lines = Vec::with_capacity(ops.len());
+    let mut new_lines = Vec::with_capacity(ops.len());
+    let (mut ol, mut nl) = (1usize, 1usize);
+    for op in &ops {
+        old_lines.push(ol);
+        new_lines.push(nl);
+        match op {
+            DiffOp::Equal(_) => {
+                ol += 1;
+                nl += 1;
+            }
+            DiffOp::Delete(_) => ol += 1,
+            DiffOp::Insert(_) => nl += 1,
+        }
+    }
+
+    let mut out = format!("--- a/{file}\n+++ b/{file}\n");
+
+    let change_indices: Vec<usize> = ops
+        .iter()
+        .enumerate()
+        .filter(|(_, op)| !matches!(op, DiffOp::Equal(_)))
+        .map(|(i, _)| i)
+        .collect();
+    if change_indices.is_empty() {
+        return out;
+    }
+
+    // group changes into hunks, extending each by DIFF_CONTEXT lines of
+    // surrounding context and merging any whose context would overlap
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    let mut start = change_indices[0].saturating_sub(DIFF_CONTEXT);
+    let mut end = (change_indices[0] + DIFF_CONTEXT).min(ops.len() - 1);
+    for &idx in &change_indices[1..] {
+        let next_start = idx.saturating_sub(DIFF_CONTEXT);
+        if next_start <= end + 1 {
+            end = (idx + DIFF_CONTEXT).min(ops.len() - 1);
+        } else {
+            hunks.push((start, end));
+            start = next_start;
+            end = (idx + DIFF_CONTEXT).min(ops.len() - 1);
+        }
+    }
+    hunks.push((start, end));
+
+    for (start, end) in hunks {
+        let old_start = old_lines[start];
+        let new_start = new_lines[start];
+        let mut old_count = 0;
+        let mut new_count = 0;
+        let mut body = String::new();
+        for op in &ops[start..=end] {
+            match op {
+                DiffOp::Equal(line) => {
+                    old_count += 1;
+                    new_count += 1;
+                    body.push_str(&format!(" {line}\n"));
+                }
+                DiffOp::Delete(line) => {
+                    old_count += 1;
+                    body.push_str(&format!("-{line}\n"));
+                }
+                DiffOp::Insert(line) => {
+                    new_count += 1;
+                    body.push_str(&format!("+{line}\n"));
+                }
+            }
+        }
+        out.push_str(&format!(
+            "@@ -{old_start},{old_count} +{new_start},{new_count} @@\n"
+        ));
+        out.push_str(&body);
+    }
+    out
+}
+
+/// applies one phase of `--fix` suggestions to `content`: interactively if
+/// `--interactive` was given, otherwise all at once. returns `content`
+/// unchanged with a count of 0 when there's nothing to do, so callers can
+/// chain phases without special-casing an empty suggestion list
+fn apply_fix_suggestions(
+    cli: &Cli,
+    display_name: &str,
+    content: &str,
+    suggestions: &[fix::Suggestion],
+) -> (String, usize) {
+    if suggestions.is_empty() {
+        return (content.to_string(), 0);
+    }
+    if cli.interactive {
+        interactive_fix(display_name, content, suggestions)
+    } else {
+        (fix::apply_suggestions(content, suggestions), suggestions.len())
+    }
+}
+
+/// walks the user through each fixable diagnostic in `content` one at a
+/// time, similar in spirit to `git add -p`: shows the span that would
+/// change and prompts accept/skip/accept-all/quit on stdin. returns the
+/// content with only the accepted edits applied, and how many were accepted
+fn interactive_fix(display_name: &str, content: &str, suggestions: &[fix::Suggestion]) -> (String, usize) {
+    use std::io::{BufRead, Write};
+
+    let mut ordered: Vec<&fix::Suggestion> = suggestions.iter().collect();
+    ordered.sort_by_key(|s| s.start_byte);
+
+    let stdin = std::io::stdin();
+    let mut accepted: Vec<fix::Suggestion> = Vec::new();
+    let mut accept_all = false;
+
+    for suggestion in ordered {
+        if accept_all {
+            accepted.push(suggestion.clone());
+            continue;
+        }
+
+        println!("{}", format!("{}: {}", display_name, suggestion.message).bold());
+        let old = &content[suggestion.start_byte..suggestion.end_byte];
+        println!("{}", format!("-{old}").red());
+        println!("{}", format!("+{}", suggestion.replacement).green());
+        print!("Apply this fix? [y,n,a=accept all,q=quit] ");
+        if std::io::stdout().flush().is_err() {
+            break;
+        }
+
+        let mut answer = String::new();
+        if stdin.lock().read_line(&mut answer).is_err() {
+            break;
+        }
+        match answer.trim() {
+            "y" | "Y" => accepted.push(suggestion.clone()),
+            "a" | "A" => {
+                accept_all = true;
+                accepted.push(suggestion.clone());
+            }
+            "q" | "Q" => break,
+            _ => {}
+        }
+    }
+
+    let accepted_count = accepted.len();
+    (fix::apply_suggestions(content, &accepted), accepted_count)
+}
+
+fn lint_file(file: &str, cli: &Cli) -> FileResult {
+    use std::io::Read;
+
+    // display name shown in output; the raw "-" argument reads as <stdin>
+    // unless the caller told us the real path via --stdin-filename
+    let display_name = if file == "-" {
+        cli.stdin_filename.as_deref().unwrap_or("<stdin>")
+    } else {
+        file
+    };
+
+    // read source into memory, from stdin when the file argument is "-"
+    let content = if file == "-" {
+        let mut buf = String::new();
+        match std::io::stdin().read_to_string(&mut buf) {
+            Ok(_) => buf,
+            Err(e) => {
+                if !cli.json {
+                    eprintln!("{} Could not read stdin: {}", "error:".red().bold(), e);
+                }
+                return FileResult {
+                    file: display_name.to_string(),
+                    errors: vec![],
+                    warnings: vec![],
+                    warning_total: 0,
+                    errors_truncated: 0,
+                    warnings_truncated: 0,
+                    stats: None,
+                    suggestions: vec![],
+                    fatal: true,
+                    patch: None,
+                };
+            }
+        }
+    } else {
+        match fs::read_to_string(file) {
+            Ok(c) => c,
+            Err(e) => {
+                if !cli.json {
+                    eprintln!(
+                        "{} Could not read file '{}': {}",
+                        "error:".red().bold(),
+                        display_name,
+                        e
+                    );
+                }
+                return FileResult {
+                    file: display_name.to_string(),
+                    errors: vec![],
+                    warnings: vec![],
+                    warning_total: 0,
+                    errors_truncated: 0,
+                    warnings_truncated: 0,
+                    stats: None,
+                    suggestions: vec![],
+                    fatal: true,
+                    patch: None,
+                };
+            }
+        }
+    };
+
+    // build lint configuration, starting from the style preset if requested
+    // and letting explicit flags override individual settings
+    let mut lint_config = if cli.style {
+        LintConfig::style_preset()
+    } else {
+        LintConfig::default()
+    };
+
+    // merge any `.lollint.toml` files between the filesystem root and the
+    // linted file's directory, closest wins; stdin only has a directory to
+    // search when --stdin-filename gave it a real path
+    let config_path = if file == "-" {
+        cli.stdin_filename.as_deref()
+    } else {
+        Some(file)
+    };
+    if let Some(path) = config_path {
+        hierconfig::apply(Path::new(path), &mut lint_config);
+    }
+
+    if cli.min_identifier_length > 0 {
+        lint_config.min_identifier_length = cli.min_identifier_length;
+    }
+    if cli.max_identifier_length > 0 {
+        lint_config.max_identifier_length = cli.max_identifier_length;
+    }
+    if cli.check_whitespace {
+        lint_config.check_whitespace = true;
+    }
+    if cli.min_comment_density > 0.0 {
+        lint_config.min_comment_density = cli.min_comment_density;
+    }
+    if cli.declarations_at_top {
+        lint_config.declarations_at_top = true;
+    }
+
+    // --fix runs four phases in order, each against the previous phase's
+    // output, rather than one combined pass against the original source:
+    //
+    //   1. textual/token-level fixes that don't need a successful parse
+    //      (trailing whitespace, missing final newline, miscased keywords)
+    //   2. inserting an empty NO WAI branch into O RLY? blocks missing one
+    //   3. turning a duplicate `I HAS A x ITZ v` into the assignment
+    //      `x R v` it almost always meant to be
+    //   4. deleting declarations that are now unused
+    //
+    // the order matters: a miscased keyword can hide a real use of a
+    // variable (`visible x` isn't a use of `x` until it's `VISIBLE x`),
+    // and turning a duplicate declaration into an assignment can turn its
+    // *original* declaration from "used" into "unused". running unused-
+    // declaration deletion last means it always sees the final picture
+    // instead of deleting something an earlier phase still needed. NO WAI
+    // insertion is purely additive and never changes what's declared or
+    // used, so it can safely run anywhere before the destructive phases.
+    // stdin has nothing on disk to write back to, so it's skipped.
+    // --fix --dry-run instead prints what would change and leaves both
+    // the file and the content fed to the linter untouched
+    let mut patch = None;
+    let (content, fixed_count) = if cli.fix && file != "-" {
+        let mut textual = fix::suggestions_for(&content);
+        textual.extend(keyword_casing_suggestions(&content));
+
+        if cli.dry_run || cli.emit_patch.is_some() {
+            let after_textual = fix::apply_suggestions(&content, &textual);
+            let no_wais = missing_no_wai_suggestions(&after_textual, &lint_config);
+            let after_no_wai = fix::apply_suggestions(&after_textual, &no_wais);
+            let duplicates = duplicate_declaration_suggestions(&after_no_wai, &lint_config);
+            let after_duplicates = fix::apply_suggestions(&after_no_wai, &duplicates);
+            let declarations = unused_declaration_suggestions(&after_duplicates, &lint_config);
+            let fixed = fix::apply_suggestions(&after_duplicates, &declarations);
+            if !textual.is_empty() || !no_wais.is_empty() || !duplicates.is_empty() || !declarations.is_empty() {
+                if cli.emit_patch.is_some() {
+                    patch = Some(unified_diff(display_name, &content, &fixed));
+                } else {
+                    print_fix_diff(display_name, &content, &fixed);
+                }
+            }
+            (content, 0)
+        } else {
+            let (after_textual, textual_count) =
+                apply_fix_suggestions(cli, display_name, &content, &textual);
+            let no_wais = missing_no_wai_suggestions(&after_textual, &lint_config);
+            let (after_no_wai, no_wai_count) =
+                apply_fix_suggestions(cli, display_name, &after_textual, &no_wais);
+            let duplicates = duplicate_declaration_suggestions(&after_no_wai, &lint_config);
+            let (after_duplicates, duplicate_count) =
+                apply_fix_suggestions(cli, display_name, &after_no_wai, &duplicates);
+            let declarations = unused_declaration_suggestions(&after_duplicates, &lint_config);
+            let (fixed, decl_count) =
+                apply_fix_suggestions(cli, display_name, &after_duplicates, &declarations);
+
+            let count = textual_count + no_wai_count + duplicate_count + decl_count;
+            if count > 0 {
+                if let Err(e) = fs::write(file, &fixed) {
+                    if !cli.json {
+                        eprintln!(
+                            "{} Could not write fixes to '{}': {}",
+                            "error:".red().bold(),
+                            display_name,
+                            e
+                        );
+                    }
+                }
+            }
+            (fixed, count)
+        }
+    } else {
+        (content, 0)
+    };
+    if fixed_count > 0 && !cli.json {
+        println!("{} fixed {} issue(s) in {}", "fix:".green().bold(), fixed_count, display_name);
+    }
+
+    // the cache only stores errors/warnings, not statistics, so a --stats
+    // run always does the full pass; otherwise a hit skips lex/parse/lint
+    // entirely
+    let cache_fingerprint = format!(
+        "{}|{}|{}|{}|{}",
+        lint_config.min_identifier_length,
+        lint_config.max_identifier_length,
+        lint_config.check_whitespace,
+        lint_config.min_comment_density,
+        lint_config.declarations_at_top,
+    );
+    let cache_key = cli
+        .cache
+        .then(|| cache::cache_key(&content, &cache_fingerprint));
+
+    // suggestions describe edits against the source currently being
+    // linted, not the cached diagnostics, so they're always recomputed
+    // fresh rather than stored alongside a cache entry
+    let mut all_suggestions = fix::suggestions_for(&content);
+    all_suggestions.extend(keyword_casing_suggestions(&content));
+    all_suggestions.extend(missing_no_wai_suggestions(&content, &lint_config));
+    all_suggestions.extend(duplicate_declaration_suggestions(&content, &lint_config));
+    all_suggestions.extend(unused_declaration_suggestions(&content, &lint_config));
+
+    let mut ranges = parse_ranges(&cli.range);
+    if let Some(base) = &cli.diff {
+        if let Some(diff_ranges) = git_diff_ranges(base, file) {
+            if diff_ranges.is_empty() {
+                // a successful diff with no hunks means the file is
+                // unchanged; report nothing rather than falling back to
+                // the "no ranges means unfiltered" convention below
+                ranges.push((0, 0));
+            } else {
+                ranges.extend(diff_ranges);
+            }
+        }
+    }
+
+    if let Some(key) = &cache_key {
+        if !cli.stats {
+            if let Some(cached) = cache::load(Path::new(&cli.cache_dir), key) {
+                let cached_errors: Vec<Diagnostic> =
+                    cached.errors.iter().map(cache::CachedDiagnostic::to_diagnostic).collect();
+                let cached_warnings: Vec<Diagnostic> =
+                    cached.warnings.iter().map(cache::CachedDiagnostic::to_diagnostic).collect();
+                let ranged_errors = filter_by_range(cached_errors, &ranges);
+                let ranged_warnings = filter_by_range(cached_warnings, &ranges);
+                let warning_total = ranged_warnings.len();
+                let warnings = if cli.quiet { vec![] } else { ranged_warnings };
+                let (errors, errors_truncated) =
+                    truncate_diagnostics(ranged_errors, cli.max_diagnostics);
+                let (warnings, warnings_truncated) =
+                    truncate_diagnostics(warnings, cli.max_diagnostics);
+                let suggestions = all_suggestions
+                    .into_iter()
+                    .filter(|s| warnings.iter().any(|w| w.message == s.message))
+                    .collect();
+                return FileResult {
+                    file: display_name.to_string(),
+                    errors,
+                    warnings,
+                    warning_total,
+                    errors_truncated,
+                    warnings_truncated,
+                    stats: None,
+                    suggestions,
+                    fatal: false,
+                    patch,
+                };
             }
-            process::exit(2);
         }
+    }
+
+    // the full lex/parse/lint pass runs on a worker thread when
+    // --timeout-per-file is set, so a pathological input (e.g. deeply
+    // nested expressions) can't hang the whole run
+    let raw = if cli.timeout_per_file > 0 {
+        run_lint_pipeline_with_timeout(
+            content.clone(),
+            lint_config,
+            cli.debug,
+            cli.stats,
+            std::time::Duration::from_secs(cli.timeout_per_file),
+        )
+    } else {
+        run_lint_pipeline(&content, &lint_config, cli.debug, cli.stats)
     };
 
-    // tokenize the source code
-    let mut lexer = Lexer::new(content.clone());
+    if raw.fatal && !cli.json {
+        let message = raw.errors.first().map(|d| d.message.as_str()).unwrap_or_default();
+        eprintln!("{} {}", "error:".red().bold(), message);
+    }
+
+    if let Some(key) = &cache_key {
+        if !cli.stats && !raw.fatal {
+            cache::store(
+                Path::new(&cli.cache_dir),
+                key,
+                &cache::CachedResult {
+                    errors: raw.errors.iter().map(cache::CachedDiagnostic::from).collect(),
+                    warnings: raw.warnings.iter().map(cache::CachedDiagnostic::from).collect(),
+                    warning_total: raw.warnings.len(),
+                },
+            );
+        }
+    }
+
+    let ranged_errors = filter_by_range(raw.errors, &ranges);
+    let ranged_warnings = filter_by_range(raw.warnings, &ranges);
+    let warning_total = ranged_warnings.len();
+
+    // --quiet drops warnings from output while summaries still report the
+    // true count, so noisy legacy codebases can focus on errors first
+    let warnings = if cli.quiet { vec![] } else { ranged_warnings };
+
+    let (errors, errors_truncated) = truncate_diagnostics(ranged_errors, cli.max_diagnostics);
+    let (warnings, warnings_truncated) = truncate_diagnostics(warnings, cli.max_diagnostics);
+    let suggestions = all_suggestions
+        .into_iter()
+        .filter(|s| warnings.iter().any(|w| w.message == s.message))
+        .collect();
+
+    FileResult {
+        file: display_name.to_string(),
+        errors,
+        warnings,
+        warning_total,
+        errors_truncated,
+        warnings_truncated,
+        stats: raw.stats,
+        suggestions,
+        fatal: raw.fatal,
+        patch,
+    }
+}
+
+/// tokenizes `content` and turns every miscased keyword into a
+/// `Suggestion`; unlike `unused_declaration_suggestions` this only needs
+/// the token stream, so it still works on input that fails to parse
+fn keyword_casing_suggestions(content: &str) -> Vec<fix::Suggestion> {
+    let tokens = Lexer::new(content.to_string()).tokenize();
+    let issues = Linter::check_keyword_casing(&tokens);
+    fix::suggestions_for_keyword_casing(content, &issues)
+}
+
+/// runs a throwaway lex/parse/lint pass to find "declared twice" errors
+/// whose second declaration is safe to autofix into an assignment, and
+/// turns each into a `Suggestion`; returns nothing for input that fails
+/// to parse, since `--fix` shouldn't touch a file lol-lint can't
+/// otherwise make sense of
+fn duplicate_declaration_suggestions(content: &str, lint_config: &LintConfig) -> Vec<fix::Suggestion> {
+    let tokens = Lexer::new(content.to_string()).tokenize();
+    let mut parser = Parser::new(tokens);
+    let program = parser.parse_program();
+    if !parser.errors.is_empty() {
+        return vec![];
+    }
+    let linter = Linter::lint_with_config(&program, lint_config);
+    fix::suggestions_for_duplicate_declarations(content, &linter.duplicate_declarations)
+}
+
+/// runs a throwaway lex/parse/lint pass to find declared-but-never-used
+/// variables that are safe to autofix by deleting their whole declaration
+/// line, and turns each into a `Suggestion`; returns nothing for input
+/// that fails to parse, since `--fix` shouldn't touch a file lol-lint
+/// can't otherwise make sense of
+fn unused_declaration_suggestions(content: &str, lint_config: &LintConfig) -> Vec<fix::Suggestion> {
+    let tokens = Lexer::new(content.to_string()).tokenize();
+    let mut parser = Parser::new(tokens);
+    let program = parser.parse_program();
+    if !parser.errors.is_empty() {
+        return vec![];
+    }
+    let linter = Linter::lint_with_config(&program, lint_config);
+    fix::suggestions_for_unused_declarations(content, &linter.unused_declarations)
+}
+
+/// runs a throwaway lex/parse/lint pass to find O RLY? blocks missing a
+/// NO WAI branch, and turns each into a `Suggestion` that inserts an empty
+/// one before OIC; returns nothing for input that fails to parse, since
+/// `--fix` shouldn't touch a file lol-lint can't otherwise make sense of
+fn missing_no_wai_suggestions(content: &str, lint_config: &LintConfig) -> Vec<fix::Suggestion> {
+    let tokens = Lexer::new(content.to_string()).tokenize();
+    let mut parser = Parser::new(tokens);
+    let program = parser.parse_program();
+    if !parser.errors.is_empty() {
+        return vec![];
+    }
+    let linter = Linter::lint_with_config(&program, lint_config);
+    fix::suggestions_for_missing_no_wai(content, &linter.missing_no_wai)
+}
+
+/// outcome of the lex/parse/lint pipeline, before quiet/truncation/caching
+/// are applied
+struct RawLintOutput {
+    errors: Vec<Diagnostic>,
+    warnings: Vec<Diagnostic>,
+    stats: Option<Stats>,
+    fatal: bool,
+}
+
+/// runs the lex/parse/lint pipeline on already-loaded source text. neither
+/// `--debug` nor `--stats` is in play here, so this delegates straight to
+/// the `lol_lint` library's `lint_source` rather than re-driving the
+/// lexer/parser/linter itself
+fn run_lint_pipeline(
+    content: &str,
+    lint_config: &LintConfig,
+    debug: bool,
+    want_stats: bool,
+) -> RawLintOutput {
+    if !debug && !want_stats {
+        let result = lol_lint::lint_source(content, lint_config);
+        return RawLintOutput {
+            errors: result.errors,
+            warnings: result.warnings,
+            stats: None,
+            fatal: result.fatal,
+        };
+    }
+
+    // `--debug` and `--stats` both need direct access to the tokens/ast
+    // that `lint_source` keeps internal, so this path still drives the
+    // pipeline by hand
+    let mut lexer = Lexer::new(content.to_string());
     let tokens = lexer.tokenize();
 
     // display tokens in debug mode
-    if cli.debug {
+    if debug {
         println!("{}", "--- Tokens ---".cyan().bold());
         for t in &tokens {
             println!("{:?}", t);
@@ -98,72 +2306,188 @@ fn main() {
         println!();
     }
 
-    // parse tokens into abstract syntax tree
-    let mut parser = Parser::new(tokens);
-    let program =
-        match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| parser.parse_program())) {
-            Ok(p) => p,
-            Err(_) => {
-                if !cli.json {
-                    eprintln!("{} Parsing failed", "error:".red().bold());
-                }
-                process::exit(2);
-            }
-        };
+    // checked on the raw token stream, before parsing, so a miscased
+    // keyword that would otherwise make the parser fail is still reported
+    let casing_warnings: Vec<lol_lint::diagnostic::Diagnostic> = Linter::check_keyword_casing(&tokens)
+        .iter()
+        .map(|issue| issue.diagnostic())
+        .collect();
+
+    // parse tokens into abstract syntax tree (cloned so the tokens remain
+    // available afterward for token-level checks like comment density).
+    // the parser never panics, so `program` is always usable, even for
+    // input with syntax errors -- see `parse_errors` below
+    let mut parser = Parser::new(tokens.clone());
+    let program = parser.parse_program();
+
+    // the parser recovers from a malformed statement by skipping it and
+    // continuing, rather than aborting the whole parse; any such statements
+    // are reported here as errors instead of being silently dropped
+    let parse_errors = parser.errors;
 
     // display ast in debug mode
-    if cli.debug {
+    if debug {
         println!("{}", "--- AST ---".cyan().bold());
         println!("{:#?}", program);
         println!();
     }
 
     // perform semantic analysis and linting
-    let linter = Linter::lint(&program);
+    let mut linter = Linter::lint_with_config(&program, lint_config);
+
+    linter.warnings.extend(casing_warnings);
+
+    if lint_config.check_whitespace {
+        linter
+            .warnings
+            .extend(Linter::check_whitespace_style(content));
+    }
+    if let Some(warning) = Linter::check_comment_density(
+        &tokens,
+        lol_lint::count_lines_of_code(content),
+        lint_config.min_comment_density,
+    ) {
+        linter.warnings.push(warning);
+    }
+
+    // resolve `BTW lol-lint-disable-next-line` suppression comments last, so
+    // they can act on warnings gathered from every check above
+    linter.warnings = Linter::apply_suppressions(content, linter.warnings);
 
     // calculate code statistics if requested
-    let stats = if cli.stats {
-        Some(calculate_stats(&program, &content))
+    let stats = if want_stats {
+        Some(calculate_stats(&program, content))
     } else {
         None
     };
 
-    // format output based on requested mode
-    if cli.json {
-        let output = JsonOutput {
-            file: cli.file,
-            errors: linter.errors.clone(),
-            warnings: linter.warnings.clone(),
-            stats,
-        };
-        println!("{}", serde_json::to_string_pretty(&output).unwrap());
+    RawLintOutput {
+        errors: parse_errors.into_iter().chain(linter.errors).collect(),
+        warnings: linter.warnings,
+        stats,
+        fatal: false,
+    }
+}
+
+/// runs the lex/parse/lint pipeline on a worker thread and gives up after
+/// `timeout`, reporting a single "analysis timed out" diagnostic instead of
+/// blocking the rest of the run; the worker thread is abandoned rather than
+/// killed, since rust has no safe way to preempt a running thread
+fn run_lint_pipeline_with_timeout(
+    content: String,
+    lint_config: LintConfig,
+    debug: bool,
+    want_stats: bool,
+    timeout: std::time::Duration,
+) -> RawLintOutput {
+    let (tx, rx) = std::sync::mpsc::channel();
+
+    std::thread::spawn(move || {
+        let result = run_lint_pipeline(&content, &lint_config, debug, want_stats);
+        let _ = tx.send(result);
+    });
+
+    match rx.recv_timeout(timeout) {
+        Ok(result) => result,
+        Err(_) => RawLintOutput {
+            errors: vec![Diagnostic::error(
+                None,
+                format!("analysis timed out after {}s", timeout.as_secs()),
+                None,
+            )],
+            warnings: vec![],
+            stats: None,
+            fatal: true,
+        },
+    }
+}
+
+/// wraps `text` in an OSC 8 hyperlink escape sequence pointing at `url`,
+/// when the terminal is expected to support it; falls back to plain text
+/// otherwise, since a dumb terminal or a pipe would print the escape codes
+/// literally
+fn hyperlink(url: &str, text: &str) -> String {
+    if colored::control::SHOULD_COLORIZE.should_colorize() {
+        format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, text)
     } else {
-        print_human_readable(&linter, stats.as_ref());
+        text.to_string()
     }
+}
 
-    // exit with appropriate code: 0 for success, 1 for lint errors
-    if linter.has_errors() {
-        process::exit(1);
+/// prefixes a diagnostic with a hyperlinked rule code badge (from its own
+/// `code` field) and turns its `(line N, column M)` position into a
+/// `file://` hyperlink, so clicking it jumps straight to the offending line
+/// in terminals that support OSC 8
+fn decorate_diagnostic(file: &str, diagnostic: &Diagnostic) -> String {
+    let mut text = diagnostic.message.clone();
+
+    if let Some(code) = diagnostic.code {
+        let doc_url = format!(
+            "{}/blob/main/README.md#{}",
+            env!("CARGO_PKG_HOMEPAGE"),
+            code.to_lowercase()
+        );
+        text = format!("[{}] {}", hyperlink(&doc_url, code), text);
+    }
+
+    if let Some(span) = diagnostic.span {
+        let position = match span.column {
+            Some(col) => format!("(line {}, column {})", span.line, col),
+            None => format!("(line {})", span.line),
+        };
+        if text.contains(&position) {
+            let path = fs::canonicalize(file)
+                .map(|p| p.display().to_string())
+                .unwrap_or_else(|_| file.to_string());
+            let file_url = format!("file://{}", path);
+            let linked = hyperlink(&file_url, &position);
+            text = text.replace(&position, &linked);
+        }
     }
+
+    text
 }
 
 /// formats and prints linting results in human-readable format with colors
-fn print_human_readable(linter: &Linter, stats: Option<&Stats>) {
+fn print_human_readable(result: &FileResult) {
     // display all errors in red
-    for error in &linter.errors {
-        println!("{}", error.red());
+    for error in &result.errors {
+        println!("{}", decorate_diagnostic(&result.file, error).red());
+    }
+    if result.errors_truncated > 0 {
+        println!(
+            "{}",
+            format!(
+                "... and {} more error{}",
+                result.errors_truncated,
+                if result.errors_truncated == 1 { "" } else { "s" }
+            )
+            .red()
+        );
     }
 
     // display all warnings in yellow
-    for warning in &linter.warnings {
-        println!("{}", warning.yellow());
+    for warning in &result.warnings {
+        println!("{}", decorate_diagnostic(&result.file, warning).yellow());
+    }
+    if result.warnings_truncated > 0 {
+        println!(
+            "{}",
+            format!(
+                "... and {} more warning{}",
+                result.warnings_truncated,
+                if result.warnings_truncated == 1 { "" } else { "s" }
+            )
+            .yellow()
+        );
     }
 
-    // print summary line with error and warning counts
-    if !linter.errors.is_empty() || !linter.warnings.is_empty() {
+    // print summary line with error and warning counts; warning_total keeps
+    // reporting the true count even when --quiet emptied result.warnings
+    if !result.errors.is_empty() || result.warning_total > 0 {
         println!();
-        let error_count = linter.errors.len();
-        let warning_count = linter.warnings.len();
+        let error_count = result.errors.len() + result.errors_truncated;
+        let warning_count = result.warning_total;
 
         let error_text = if error_count > 0 {
             format!(
@@ -193,7 +2517,7 @@ fn print_human_readable(linter: &Linter, stats: Option<&Stats>) {
     }
 
     // display statistics if available
-    if let Some(s) = stats {
+    if let Some(s) = &result.stats {
         println!();
         println!("{}", "--- Statistics ---".cyan().bold());
         println!("Lines of code:  {}", s.lines_of_code);
@@ -204,16 +2528,129 @@ fn print_human_readable(linter: &Linter, stats: Option<&Stats>) {
     }
 }
 
+/// prints diagnostics as github actions workflow commands so they show up
+/// as inline annotations on the pull request diff
+fn print_github_annotations(result: &FileResult) {
+    for error in &result.errors {
+        println!("{}", github_annotation("error", &result.file, error));
+    }
+    for warning in &result.warnings {
+        println!("{}", github_annotation("warning", &result.file, warning));
+    }
+}
+
+/// builds a single `::error file=...,line=...,col=...::message` (or
+/// `::warning`) workflow command from a diagnostic
+fn github_annotation(level: &str, file: &str, diagnostic: &Diagnostic) -> String {
+    match diagnostic.span.and_then(|s| s.column.map(|col| (s.line, col))) {
+        Some((line, col)) => format!(
+            "::{} file={},line={},col={}::{}",
+            level, file, line, col, diagnostic.message
+        ),
+        None => format!("::{} file={}::{}", level, file, diagnostic.message),
+    }
+}
+
+/// prints all results as a gitlab code quality report artifact
+fn print_gitlab_report(results: &[FileResult]) {
+    let mut issues = Vec::new();
+
+    for result in results {
+        for error in &result.errors {
+            issues.push(gitlab_issue(&result.file, "major", error));
+        }
+        for warning in &result.warnings {
+            issues.push(gitlab_issue(&result.file, "minor", warning));
+        }
+    }
+
+    println!("{}", serde_json::to_string_pretty(&issues).unwrap());
+}
+
+/// builds one gitlab code quality issue from a diagnostic
+fn gitlab_issue(file: &str, severity: &str, diagnostic: &Diagnostic) -> GitlabIssue {
+    let line = diagnostic.span.map(|s| s.line).unwrap_or(1);
+
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    file.hash(&mut hasher);
+    diagnostic.message.hash(&mut hasher);
+    let fingerprint = format!("{:x}", hasher.finish());
+
+    GitlabIssue {
+        description: diagnostic.message.clone(),
+        check_name: "lol-lint".to_string(),
+        fingerprint,
+        severity: severity.to_string(),
+        location: GitlabLocation {
+            path: file.to_string(),
+            lines: GitlabLines { begin: line },
+        },
+    }
+}
+
+/// prints all results as a single rdjson document for reviewdog to consume
+fn print_rdjson_report(results: &[FileResult]) {
+    let mut diagnostics = Vec::new();
+
+    for result in results {
+        for error in &result.errors {
+            diagnostics.push(rdjson_diagnostic(&result.file, "ERROR", error));
+        }
+        for warning in &result.warnings {
+            diagnostics.push(rdjson_diagnostic(&result.file, "WARNING", warning));
+        }
+    }
+
+    let report = RdjsonReport {
+        source: RdjsonSource {
+            name: "lol-lint".to_string(),
+        },
+        diagnostics,
+    };
+
+    println!("{}", serde_json::to_string_pretty(&report).unwrap());
+}
+
+/// builds one rdjson diagnostic from a lol-lint diagnostic, falling back to
+/// line 1 column 1 when it carries no position
+fn rdjson_diagnostic(file: &str, severity: &str, diagnostic: &Diagnostic) -> RdjsonDiagnostic {
+    let line = diagnostic.span.map(|s| s.line).unwrap_or(1);
+    let column = diagnostic.span.and_then(|s| s.column).unwrap_or(1);
+
+    RdjsonDiagnostic {
+        message: diagnostic.message.clone(),
+        location: RdjsonLocation {
+            path: file.to_string(),
+            range: RdjsonRange {
+                start: RdjsonPosition { line, column },
+            },
+        },
+        severity: severity.to_string(),
+    }
+}
+
+/// prints diagnostics as plain `file:line:col: severity: message` lines
+/// with no color, for `:make`/compilation-mode integration
+fn print_quickfix(result: &FileResult) {
+    for error in &result.errors {
+        println!("{}", quickfix_line(&result.file, "error", error));
+    }
+    for warning in &result.warnings {
+        println!("{}", quickfix_line(&result.file, "warning", warning));
+    }
+}
+
+/// builds one quickfix line, falling back to line 1 column 1 when a
+/// diagnostic carries no position
+fn quickfix_line(file: &str, severity: &str, diagnostic: &Diagnostic) -> String {
+    let line = diagnostic.span.map(|s| s.line).unwrap_or(1);
+    let col = diagnostic.span.and_then(|s| s.column).unwrap_or(1);
+    format!("{}:{}:{}: {}: {}", file, line, col, severity, diagnostic.message)
+}
+
 /// calculates code statistics by analyzing the ast and source content
 fn calculate_stats(program: &ast::Program, content: &str) -> Stats {
-    // count non-empty, non-comment lines
-    let lines_of_code = content
-        .lines()
-        .filter(|line| {
-            let trimmed = line.trim();
-            !trimmed.is_empty() && !trimmed.starts_with("BTW") && !trimmed.starts_with("OBTW")
-        })
-        .count();
+    let lines_of_code = lol_lint::count_lines_of_code(content);
 
     let mut variables = 0;
     let mut loops = 0;
@@ -270,6 +2707,8 @@ fn count_in_block(
             ast::Statement::Expr { .. } => {
                 *exprs += 1;
             }
+            ast::Statement::Gtfo { .. } => {}
+            ast::Statement::Gimmeh { .. } => {}
         }
     }
 }
@@ -0,0 +1,230 @@
+// merge: unions the `--json` reports from several sharded lol-lint runs
+// (e.g. a CI matrix that splits the file list across jobs) back into one
+// report with re-sorted files and recomputed totals
+
+use lol_lint::rules;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Deserialize)]
+struct ShardReport {
+    files: Vec<ShardFile>,
+}
+
+#[derive(Deserialize)]
+struct ShardFile {
+    file: String,
+    #[serde(default)]
+    errors: Vec<String>,
+    #[serde(default)]
+    warnings: Vec<String>,
+    #[serde(default)]
+    errors_truncated: usize,
+    #[serde(default)]
+    warnings_truncated: usize,
+    stats: Option<ShardStats>,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+struct ShardStats {
+    lines_of_code: usize,
+    variables: usize,
+    loops: usize,
+    conditionals: usize,
+    expressions: usize,
+}
+
+#[derive(Serialize)]
+struct MergedFile {
+    file: String,
+    errors: Vec<String>,
+    warnings: Vec<String>,
+    errors_truncated: usize,
+    warnings_truncated: usize,
+    stats: Option<ShardStats>,
+}
+
+#[derive(Serialize)]
+struct RuleCount {
+    code: &'static str,
+    count: usize,
+}
+
+#[derive(Serialize)]
+struct MergedReport {
+    schema_version: u32,
+    files: Vec<MergedFile>,
+    rule_summary: Vec<RuleCount>,
+}
+
+/// splits `merge` subcommand args into the input glob patterns and the
+/// `-o`/`--output` path; the output path is required, since printing a
+/// merged report to stdout would just be `cat shard*.json | jq -s` with
+/// extra steps
+fn parse_args(args: &[String]) -> Result<(Vec<String>, String), String> {
+    let mut inputs = Vec::new();
+    let mut output = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "-o" || arg == "--output" {
+            output = iter.next().cloned();
+        } else {
+            inputs.push(arg.clone());
+        }
+    }
+
+    let output = output.ok_or_else(|| "missing required -o/--output <path>".to_string())?;
+    if inputs.is_empty() {
+        return Err("no input reports given".to_string());
+    }
+    Ok((inputs, output))
+}
+
+/// runs `lol-lint merge <shard...> -o <output>`; returns the process exit code
+pub fn run(args: &[String]) -> i32 {
+    let (patterns, output_path) = match parse_args(args) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            eprintln!("usage: lol-lint merge <shard*.json...> -o <combined.json>");
+            return 2;
+        }
+    };
+
+    let mut paths = Vec::new();
+    for pattern in &patterns {
+        let matches: Vec<String> = glob::glob(pattern)
+            .into_iter()
+            .flatten()
+            .filter_map(Result::ok)
+            .map(|p| p.to_string_lossy().into_owned())
+            .collect();
+        if matches.is_empty() {
+            paths.push(pattern.clone());
+        } else {
+            paths.extend(matches);
+        }
+    }
+
+    // keyed by file path so the same file appearing in more than one shard
+    // (shouldn't happen for a well-partitioned run, but is cheap to
+    // tolerate) has its diagnostics unioned rather than duplicated
+    let mut by_file: BTreeMap<String, MergedFile> = BTreeMap::new();
+
+    for path in &paths {
+        let text = match std::fs::read_to_string(path) {
+            Ok(t) => t,
+            Err(e) => {
+                eprintln!("error: could not read '{}': {}", path, e);
+                return 2;
+            }
+        };
+        let shard: ShardReport = match serde_json::from_str(&text) {
+            Ok(r) => r,
+            Err(e) => {
+                eprintln!("error: could not parse '{}': {}", path, e);
+                return 2;
+            }
+        };
+
+        for file in shard.files {
+            let entry = by_file.entry(file.file.clone()).or_insert_with(|| MergedFile {
+                file: file.file.clone(),
+                errors: vec![],
+                warnings: vec![],
+                errors_truncated: 0,
+                warnings_truncated: 0,
+                stats: None,
+            });
+            for e in file.errors {
+                if !entry.errors.contains(&e) {
+                    entry.errors.push(e);
+                }
+            }
+            for w in file.warnings {
+                if !entry.warnings.contains(&w) {
+                    entry.warnings.push(w);
+                }
+            }
+            entry.errors_truncated += file.errors_truncated;
+            entry.warnings_truncated += file.warnings_truncated;
+            if entry.stats.is_none() {
+                entry.stats = file.stats;
+            }
+        }
+    }
+
+    let mut counts: std::collections::HashMap<&'static str, usize> =
+        std::collections::HashMap::new();
+    for file in by_file.values() {
+        for message in file.errors.iter().chain(&file.warnings) {
+            if let Some(code) = rules::classify(message) {
+                *counts.entry(code).or_insert(0) += 1;
+            }
+        }
+    }
+    let mut rule_summary: Vec<RuleCount> = counts
+        .into_iter()
+        .map(|(code, count)| RuleCount { code, count })
+        .collect();
+    rule_summary.sort_by(|a, b| b.count.cmp(&a.count).then(a.code.cmp(b.code)));
+
+    let report = MergedReport {
+        schema_version: crate::JSON_SCHEMA_VERSION,
+        files: by_file.into_values().collect(),
+        rule_summary,
+    };
+
+    let json = match serde_json::to_string_pretty(&report) {
+        Ok(j) => j,
+        Err(e) => {
+            eprintln!("error: could not serialize merged report: {}", e);
+            return 2;
+        }
+    };
+
+    if let Err(e) = std::fs::write(&output_path, json) {
+        eprintln!("error: could not write '{}': {}", output_path, e);
+        return 2;
+    }
+
+    println!(
+        "merged {} shard(s) into '{}' ({} files)",
+        paths.len(),
+        output_path,
+        report.files.len()
+    );
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_args_separates_the_output_flag_from_input_patterns() {
+        let args = vec!["a.json".to_string(), "b.json".to_string(), "-o".to_string(), "out.json".to_string()];
+        let (inputs, output) = parse_args(&args).unwrap();
+        assert_eq!(inputs, vec!["a.json".to_string(), "b.json".to_string()]);
+        assert_eq!(output, "out.json");
+    }
+
+    #[test]
+    fn parse_args_accepts_the_long_output_flag() {
+        let args = vec!["--output".to_string(), "out.json".to_string(), "a.json".to_string()];
+        let (inputs, output) = parse_args(&args).unwrap();
+        assert_eq!(inputs, vec!["a.json".to_string()]);
+        assert_eq!(output, "out.json");
+    }
+
+    #[test]
+    fn parse_args_rejects_a_missing_output_flag() {
+        assert!(parse_args(&["a.json".to_string()]).is_err());
+    }
+
+    #[test]
+    fn parse_args_rejects_no_inputs() {
+        assert!(parse_args(&["-o".to_string(), "out.json".to_string()]).is_err());
+    }
+}
@@ -0,0 +1,135 @@
+// minify: strips comments, blank lines, and indentation from lolcode
+// source while preserving semantics, for code-golf and for embedding a
+// program somewhere size matters (e.g. inline in another file's string
+// literal) -- the opposite transform of `fmt`, and built the same way:
+// off the token stream so it can still process a file the parser would
+// reject, rather than the ast
+//
+// lolcode has no statement separator besides a newline (no comma, no
+// semicolon), so unlike whitespace-only indentation, a line boundary is
+// never cosmetic here and this never collapses two statement lines into
+// one -- only the comments and the leading/inter-token whitespace on
+// each line are unnecessary
+
+use crate::editorconfig::EditorConfig;
+use lol_lint::lexer::Lexer;
+use lol_lint::types::{Token, TokenKind};
+use std::path::Path;
+
+/// re-renders `source` with every comment and blank line removed and
+/// every remaining line's tokens joined by a single space with no
+/// leading indentation
+pub fn minify_source(source: &str, config: &EditorConfig) -> String {
+    let tokens = Lexer::new(source.to_string()).tokenize();
+
+    let mut lines: Vec<Vec<Token>> = vec![vec![]];
+    for token in tokens {
+        if let TokenKind::Newline = token.kind {
+            lines.push(vec![]);
+        } else {
+            lines.last_mut().unwrap().push(token);
+        }
+    }
+
+    let rendered_lines: Vec<String> = lines
+        .iter()
+        .filter_map(|line| {
+            let code_tokens: Vec<&Token> = line.iter().filter(|t| !matches!(t.kind, TokenKind::Comment(_))).collect();
+            if code_tokens.is_empty() {
+                None
+            } else {
+                Some(code_tokens.iter().map(|t| render_token(t)).collect::<Vec<_>>().join(" "))
+            }
+        })
+        .collect();
+
+    let mut result = rendered_lines.join(config.newline());
+    if config.insert_final_newline {
+        if !result.ends_with(config.newline()) {
+            result.push_str(config.newline());
+        }
+    } else {
+        while result.ends_with(config.newline()) {
+            result.truncate(result.len() - config.newline().len());
+        }
+    }
+    result
+}
+
+fn render_token(token: &Token) -> String {
+    match &token.kind {
+        TokenKind::Keyword(k) => k.clone(),
+        TokenKind::Identifier(s) => s.clone(),
+        TokenKind::Number(s) => s.clone(),
+        TokenKind::StringLiteral(s) => format!("\"{s}\""),
+        TokenKind::Comment(_) | TokenKind::Newline => String::new(),
+    }
+}
+
+/// entry point for the `minify` subcommand: rewrites each file in place
+/// with comments and blank lines stripped. mirrors `fmt::run`'s
+/// self-contained argument handling and in-place-by-default behavior
+pub fn run(args: &[String]) -> i32 {
+    if args.is_empty() {
+        eprintln!("usage: lol-lint minify <file.lol>...");
+        return 2;
+    }
+
+    let mut had_error = false;
+    for file in args {
+        let source = match std::fs::read_to_string(file) {
+            Ok(s) => s,
+            Err(e) => {
+                eprintln!("error: could not read '{file}': {e}");
+                had_error = true;
+                continue;
+            }
+        };
+
+        let config = crate::editorconfig::resolve(Path::new(file));
+        let minified = minify_source(&source, &config);
+        if minified == source {
+            continue;
+        }
+        if let Err(e) = std::fs::write(file, &minified) {
+            eprintln!("error: could not write '{file}': {e}");
+            had_error = true;
+        } else {
+            println!("minified {file}");
+        }
+    }
+
+    if had_error {
+        2
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_comments_blank_lines_and_indentation() {
+        let source = "HAI 1.2\nBTW a comment\n\n    VISIBLE \"hi\"\nKTHXBYE\n";
+        let minified = minify_source(source, &EditorConfig::default());
+        assert_eq!(minified, "HAI 1.2\nVISIBLE \"hi\"\nKTHXBYE\n");
+    }
+
+    #[test]
+    fn never_merges_two_statement_lines_into_one() {
+        // unlike whitespace-only indentation, a newline is never cosmetic
+        // in lolcode -- there's no statement separator besides it
+        let source = "HAI 1.2\nVISIBLE 1\nVISIBLE 2\nKTHXBYE\n";
+        let minified = minify_source(source, &EditorConfig::default());
+        assert_eq!(minified.lines().count(), 4);
+    }
+
+    #[test]
+    fn omits_the_final_newline_when_the_config_says_not_to_insert_one() {
+        let config = EditorConfig { insert_final_newline: false, ..EditorConfig::default() };
+        let minified = minify_source("HAI 1.2\nKTHXBYE\n", &config);
+        assert!(!minified.ends_with('\n'));
+    }
+}
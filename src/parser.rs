@@ -2,12 +2,18 @@
 // performs strict syntax validation and builds ast nodes
 
 use crate::ast::{Block, Expression, Position, Program, Statement};
+use crate::diagnostic::{Diagnostic, Span};
 use crate::types::{Token, TokenKind};
 
 /// parser state for building ast from tokens
 pub struct Parser {
     tokens: Vec<Token>,
     position: usize,
+    /// syntax errors recorded by `error`; a statement that recorded one or
+    /// more of these is dropped by `parse_statement_recovering` rather than
+    /// kept, but parsing itself never stops because of them -- see
+    /// `synchronize`
+    pub errors: Vec<Diagnostic>,
 }
 
 impl Parser {
@@ -16,6 +22,7 @@ impl Parser {
         Self {
             tokens,
             position: 0,
+            errors: Vec::new(),
         }
     }
 
@@ -36,34 +43,85 @@ impl Parser {
         }
     }
 
-    /// reports a parse error with position information and panics
-    fn error(&self, msg: &str) -> ! {
-        if let Some(token) = self.current() {
-            panic!(
-                "Parse error at line {}, column {}: {}",
-                token.line, token.column, msg
-            );
+    /// records a syntax error at the current position; unlike a hard
+    /// failure this doesn't stop parsing on its own -- callers that can't
+    /// sensibly continue building whatever they were building return early
+    /// afterward, and `parse_statement_recovering` drops and resynchronizes
+    /// past any statement that recorded one
+    fn error(&mut self, msg: &str) {
+        let (message, span) = if let Some(token) = self.current() {
+            let pos = Position::from_token(token);
+            (
+                format!(
+                    "Parse error at line {}, column {}: {}",
+                    token.line, token.column, msg
+                ),
+                Some(Span::from_position(&pos)),
+            )
+        } else {
+            (format!("Parse error: {} (at end of file)", msg), None)
+        };
+        self.errors.push(Diagnostic::error(None, message, span));
+    }
+
+    /// parses one statement and, if it recorded a syntax error, drops it
+    /// and calls `synchronize` to resume at the next safe point instead of
+    /// leaving the parser stuck re-reading the same broken tokens. this is
+    /// what lets one bad statement get skipped while the rest of the file
+    /// still parses and reports its own errors, rather than the whole file
+    /// failing on the first problem
+    fn parse_statement_recovering(&mut self) -> Option<Statement> {
+        let errors_before = self.errors.len();
+        let stmt = self.parse_statement();
+        if self.errors.len() > errors_before {
+            self.synchronize();
+            None
         } else {
-            panic!("Parse error: {} (at end of file)", msg);
+            stmt
         }
     }
 
-    /// expects a specific keyword and advances, errors if not found
-    fn expect(&mut self, expected: &str) {
-        if let Some(token) = self.current() {
+    /// skips tokens after a parse error until a safe point to resume from:
+    /// the next newline (consumed, so the following statement starts
+    /// fresh) or a block-terminator keyword (left in place for the
+    /// enclosing block's loop to detect and act on)
+    fn synchronize(&mut self) {
+        while let Some(token) = self.current() {
             match &token.kind {
+                TokenKind::Newline => {
+                    self.advance();
+                    return;
+                }
+                TokenKind::Keyword(k) if matches!(k.as_str(), "KTHXBYE" | "OIC" | "WAI" | "MKAY") => {
+                    return;
+                }
+                _ => self.advance(),
+            }
+        }
+    }
+
+    /// expects a specific keyword and advances, recording an error and
+    /// returning `None` if it's not found
+    fn expect(&mut self, expected: &str) -> Option<()> {
+        let found = self.current().cloned();
+        match found {
+            Some(token) => match &token.kind {
                 TokenKind::Keyword(k) if k == expected => {
                     self.advance();
+                    Some(())
                 }
                 _ => {
                     self.error(&format!(
                         "Expected '{}', but found {:?}",
                         expected, token.kind
                     ));
+                    None
                 }
+            },
+            None => {
+                self.error(&format!("Expected '{}', but reached end of file", expected));
+                None
             }
-        } else {
-            self.error(&format!("Expected '{}', but reached end of file", expected));
         }
     }
 
@@ -101,15 +159,20 @@ impl Parser {
                     self.advance();
                 }
                 _ => {
-                    if let Some(stmt) = self.parse_statement() {
+                    if let Some(stmt) = self.parse_statement_recovering() {
                         statements.push(stmt);
                     }
                 }
             }
         }
 
-        // reached end without kthxbye
+        // reached end without kthxbye; report it and hand back whatever
+        // statements were successfully parsed rather than discarding them
         self.error("Expected 'KTHXBYE' at end of program");
+        Program {
+            version,
+            body: Block { statements },
+        }
     }
 
     /// parses a block of statements until an end keyword is reached
@@ -136,7 +199,7 @@ impl Parser {
                     self.advance();
                 }
                 _ => {
-                    if let Some(stmt) = self.parse_statement() {
+                    if let Some(stmt) = self.parse_statement_recovering() {
                         statements.push(stmt);
                     }
                 }
@@ -148,10 +211,7 @@ impl Parser {
 
     /// parses a single statement (declaration, assignment, visible, control flow)
     fn parse_statement(&mut self) -> Option<Statement> {
-        let pos = self.current().map(|t| Position {
-            line: t.line,
-            column: t.column,
-        })?;
+        let pos = self.current().map(Position::from_token)?;
         // check for block structures first (conditionals and loops)
         if let Some(token) = self.current() {
             if let TokenKind::Keyword(k) = &token.kind {
@@ -219,12 +279,17 @@ impl Parser {
                             };
 
                             // expect oic to close the conditional
+                            let oic_pos = self
+                                .current()
+                                .map(Position::from_token)
+                                .unwrap_or_else(|| pos.clone());
                             self.expect("OIC");
 
                             return Some(Statement::ORly {
                                 ya_rly,
                                 no_wai,
                                 pos,
+                                oic_pos,
                             });
                         }
                     }
@@ -269,7 +334,7 @@ impl Parser {
                     // parse first expression if present
                     if let Some(t) = self.current() {
                         if !matches!(&t.kind, TokenKind::Newline) {
-                            expressions.push(self.parse_expression());
+                            expressions.push(self.parse_expression()?);
                         }
                     }
 
@@ -298,13 +363,14 @@ impl Parser {
                                 kind: TokenKind::Keyword(_),
                                 ..
                             }) => {
-                                expressions.push(self.parse_expression());
+                                expressions.push(self.parse_expression()?);
                             }
                             Some(t) => {
                                 self.error(&format!(
                                     "Unexpected token after expression in VISIBLE: {:?}",
                                     t.kind
                                 ));
+                                break;
                             }
                         }
                     }
@@ -312,6 +378,33 @@ impl Parser {
                     Some(Statement::Visible { expressions, pos })
                 }
 
+                // gtfo - breaks out of the enclosing loop
+                TokenKind::Keyword(k) if k == "GTFO" => {
+                    self.advance();
+                    Some(Statement::Gtfo { pos })
+                }
+
+                // gimmeh - reads a raw yarn from stdin into a variable
+                TokenKind::Keyword(k) if k == "GIMMEH" => {
+                    self.advance();
+
+                    let name = if let Some(t) = self.current() {
+                        if let TokenKind::Identifier(id) = &t.kind {
+                            let n = id.clone();
+                            self.advance();
+                            n
+                        } else {
+                            self.error("Expected identifier after GIMMEH");
+                            return None;
+                        }
+                    } else {
+                        self.error("Expected identifier after GIMMEH");
+                        return None;
+                    };
+
+                    Some(Statement::Gimmeh { name, pos })
+                }
+
                 // i has a - variable declaration
                 TokenKind::Keyword(k) if k == "I" => {
                     self.advance();
@@ -326,16 +419,18 @@ impl Parser {
                             n
                         } else {
                             self.error("Expected identifier after I HAS A");
+                            return None;
                         }
                     } else {
                         self.error("Expected identifier after I HAS A");
+                        return None;
                     };
 
                     // check for optional itz initialization
                     let value = if let Some(t) = self.current() {
                         if matches!(&t.kind, TokenKind::Keyword(k) if k == "ITZ") {
                             self.advance();
-                            let expr = self.parse_expression();
+                            let expr = self.parse_expression()?;
 
                             // ensure expression ends at newline or eof
                             if let Some(next) = self.current() {
@@ -366,7 +461,7 @@ impl Parser {
                     if let Some(t) = self.current() {
                         if matches!(&t.kind, TokenKind::Keyword(k) if k == "R") {
                             self.advance();
-                            let expr = self.parse_expression();
+                            let expr = self.parse_expression()?;
 
                             // ensure expression ends at newline or eof
                             if let Some(next) = self.current() {
@@ -394,7 +489,7 @@ impl Parser {
                         "SUM" | "DIFF" | "PRODUKT" | "QUOSHUNT" | "MOD" | "BOTH" | "DIFFRINT"
                     ) =>
                 {
-                    let expr = self.parse_expression();
+                    let expr = self.parse_expression()?;
 
                     // ensure expression ends at newline or eof
                     if let Some(next) = self.current() {
@@ -432,7 +527,7 @@ impl Parser {
             if let TokenKind::Keyword(k) = &token.kind {
                 if k == "IM" {
                     if let Some(t1) = self.peek(1) {
-                        if matches!(&t1.kind, TokenKind::Identifier(id) if id == "OUTTA") {
+                        if matches!(&t1.kind, TokenKind::Keyword(k) if k == "OUTTA") {
                             if let Some(t2) = self.peek(2) {
                                 if matches!(&t2.kind, TokenKind::Keyword(k) if k == "YR") {
                                     if let Some(t3) = self.peek(3) {
@@ -457,7 +552,7 @@ impl Parser {
                     self.advance();
                 }
                 _ => {
-                    if let Some(stmt) = self.parse_statement() {
+                    if let Some(stmt) = self.parse_statement_recovering() {
                         statements.push(stmt);
                     }
                 }
@@ -467,134 +562,144 @@ impl Parser {
         Block { statements }
     }
 
-    /// parses an expression (literal, identifier, or operation)
-    fn parse_expression(&mut self) -> Expression {
-        let token = self.current().expect("unexpected EOF in expression");
-        let pos = Position {
-            line: token.line,
-            column: token.column,
+    /// parses an expression (literal, identifier, or operation); records
+    /// an error and returns `None` if the current token can't start one or
+    /// a required piece of an operator expression is missing. public so a
+    /// caller that only has a bare expression -- not a whole program, e.g.
+    /// `lol-lint eval` -- can still drive the parser directly
+    pub fn parse_expression(&mut self) -> Option<Expression> {
+        let token = match self.current() {
+            Some(t) => t.clone(),
+            None => {
+                self.error("Unexpected end of file in expression");
+                return None;
+            }
         };
+        let pos = Position::from_token(&token);
 
         match &token.kind {
             // number literal
             TokenKind::Number(n) => {
                 let num = n.clone();
                 self.advance();
-                Expression::Number(num, pos)
+                Some(Expression::Number(num, pos))
             }
 
             // string literal
             TokenKind::StringLiteral(s) => {
                 let string = s.clone();
                 self.advance();
-                Expression::String(string, pos)
+                Some(Expression::String(string, pos))
             }
 
             // identifier
             TokenKind::Identifier(id) => {
                 let ident = id.clone();
                 self.advance();
-                Expression::Identifier(ident, pos)
+                Some(Expression::Identifier(ident, pos))
             }
 
             // sum of - addition
             TokenKind::Keyword(k) if k == "SUM" => {
                 self.advance();
-                self.expect("OF");
-                let left = self.parse_expression();
-                self.expect("AN");
-                let right = self.parse_expression();
-                Expression::Sum {
+                self.expect("OF")?;
+                let left = self.parse_expression()?;
+                self.expect("AN")?;
+                let right = self.parse_expression()?;
+                Some(Expression::Sum {
                     left: Box::new(left),
                     right: Box::new(right),
                     pos,
-                }
+                })
             }
 
             // diff of - subtraction
             TokenKind::Keyword(k) if k == "DIFF" => {
                 self.advance();
-                self.expect("OF");
-                let left = self.parse_expression();
-                self.expect("AN");
-                let right = self.parse_expression();
-                Expression::Diff {
+                self.expect("OF")?;
+                let left = self.parse_expression()?;
+                self.expect("AN")?;
+                let right = self.parse_expression()?;
+                Some(Expression::Diff {
                     left: Box::new(left),
                     right: Box::new(right),
                     pos,
-                }
+                })
             }
 
             // produkt of - multiplication
             TokenKind::Keyword(k) if k == "PRODUKT" => {
                 self.advance();
-                self.expect("OF");
-                let left = self.parse_expression();
-                self.expect("AN");
-                let right = self.parse_expression();
-                Expression::Produkt {
+                self.expect("OF")?;
+                let left = self.parse_expression()?;
+                self.expect("AN")?;
+                let right = self.parse_expression()?;
+                Some(Expression::Produkt {
                     left: Box::new(left),
                     right: Box::new(right),
                     pos,
-                }
+                })
             }
 
             // quoshunt of - division
             TokenKind::Keyword(k) if k == "QUOSHUNT" => {
                 self.advance();
-                self.expect("OF");
-                let left = self.parse_expression();
-                self.expect("AN");
-                let right = self.parse_expression();
-                Expression::Quoshunt {
+                self.expect("OF")?;
+                let left = self.parse_expression()?;
+                self.expect("AN")?;
+                let right = self.parse_expression()?;
+                Some(Expression::Quoshunt {
                     left: Box::new(left),
                     right: Box::new(right),
                     pos,
-                }
+                })
             }
 
             // mod of - modulo
             TokenKind::Keyword(k) if k == "MOD" => {
                 self.advance();
-                self.expect("OF");
-                let left = self.parse_expression();
-                self.expect("AN");
-                let right = self.parse_expression();
-                Expression::Mod {
+                self.expect("OF")?;
+                let left = self.parse_expression()?;
+                self.expect("AN")?;
+                let right = self.parse_expression()?;
+                Some(Expression::Mod {
                     left: Box::new(left),
                     right: Box::new(right),
                     pos,
-                }
+                })
             }
 
             // both saem - equality comparison
             TokenKind::Keyword(k) if k == "BOTH" => {
                 self.advance();
-                self.expect("SAEM");
-                let left = self.parse_expression();
-                self.expect("AN");
-                let right = self.parse_expression();
-                Expression::BothSaem {
+                self.expect("SAEM")?;
+                let left = self.parse_expression()?;
+                self.expect("AN")?;
+                let right = self.parse_expression()?;
+                Some(Expression::BothSaem {
                     left: Box::new(left),
                     right: Box::new(right),
                     pos,
-                }
+                })
             }
 
             // diffrint - inequality comparison
             TokenKind::Keyword(k) if k == "DIFFRINT" => {
                 self.advance();
-                let left = self.parse_expression();
-                self.expect("AN");
-                let right = self.parse_expression();
-                Expression::Diffrint {
+                let left = self.parse_expression()?;
+                self.expect("AN")?;
+                let right = self.parse_expression()?;
+                Some(Expression::Diffrint {
                     left: Box::new(left),
                     right: Box::new(right),
                     pos,
-                }
+                })
             }
 
-            _ => self.error(&format!("Unexpected token in expression: {:?}", token.kind)),
+            _ => {
+                self.error(&format!("Unexpected token in expression: {:?}", token.kind));
+                None
+            }
         }
     }
 }
@@ -0,0 +1,83 @@
+// python: a pyo3 module publishing this crate's lint/parse api as
+// `lollint`, so automated-grading scripts for lolcode coursework can call
+// into the linter in-process instead of shelling out to the `lol-lint`
+// binary and scraping its stdout
+//
+// built with maturin, e.g. `maturin build --release --features python`
+//
+// pyo3's `#[pyfunction]`/`#[pymodule]` macros expand into code clippy
+// flags as a no-op `Into`/`From` conversion on every wrapped function;
+// this is a known false positive in the macro expansion, not this
+// module's own code
+#![allow(clippy::useless_conversion)]
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+
+/// lints `source` and returns `(errors, warnings)` as lists of message
+/// strings. `config_json` is an optional json-encoded [`LintConfig`];
+/// omitted or invalid json falls back to [`LintConfig::default`]
+#[pyfunction]
+#[pyo3(signature = (source, config_json=None))]
+fn lint_source(source: &str, config_json: Option<&str>) -> PyResult<(Vec<String>, Vec<String>)> {
+    let config = config_json
+        .and_then(|json| serde_json::from_str(json).ok())
+        .unwrap_or_default();
+    let result = crate::lint_source(source, &config);
+    Ok((
+        result.errors.into_iter().map(|d| d.message).collect(),
+        result.warnings.into_iter().map(|d| d.message).collect(),
+    ))
+}
+
+/// parses `source` and returns its ast as a python dict, in the same shape
+/// as `lol-lint --emit ast`, for scripts that want to inspect structure
+/// rather than just diagnostics
+#[pyfunction]
+fn parse_to_dict(py: Python<'_>, source: &str) -> PyResult<PyObject> {
+    let mut lexer = crate::lexer::Lexer::new(source.to_string());
+    let tokens = lexer.tokenize();
+    let mut parser = crate::parser::Parser::new(tokens);
+    let program = parser.parse_program();
+
+    let value = serde_json::to_value(&program).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    json_to_py(py, &value)
+}
+
+/// recursively converts a [`serde_json::Value`] into the equivalent python
+/// object, since the ast's `Serialize` impl is the only shape of it we
+/// have on hand and pulling in a dedicated json-to-python bridge crate
+/// isn't worth it for one function
+fn json_to_py(py: Python<'_>, value: &serde_json::Value) -> PyResult<PyObject> {
+    Ok(match value {
+        serde_json::Value::Null => py.None(),
+        serde_json::Value::Bool(b) => b.into_py(py),
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(i) => i.into_py(py),
+            None => n.as_f64().unwrap_or_default().into_py(py),
+        },
+        serde_json::Value::String(s) => s.into_py(py),
+        serde_json::Value::Array(items) => {
+            let list = PyList::empty_bound(py);
+            for item in items {
+                list.append(json_to_py(py, item)?)?;
+            }
+            list.into_py(py)
+        }
+        serde_json::Value::Object(fields) => {
+            let dict = PyDict::new_bound(py);
+            for (key, field) in fields {
+                dict.set_item(key, json_to_py(py, field)?)?;
+            }
+            dict.into_py(py)
+        }
+    })
+}
+
+#[pymodule]
+fn lollint(_py: Python<'_>, module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_function(wrap_pyfunction!(lint_source, module)?)?;
+    module.add_function(wrap_pyfunction!(parse_to_dict, module)?)?;
+    Ok(())
+}
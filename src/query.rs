@@ -0,0 +1,62 @@
+// query: a tiny in-process memoization layer over `lint_source`, for
+// long-lived callers (the lsp server, the daemon) that can see the same
+// content more than once in a run -- e.g. `textDocument/didSave` firing
+// right after `didChange` with an unchanged buffer
+//
+// this is whole-file memoization keyed by content and config, not a
+// genuine incremental query engine (salsa or otherwise) that recomputes
+// only what a single edit invalidated below the file level. this crate's
+// lexer/parser/linter aren't structured as separable, individually
+// cacheable sub-queries -- each stage consumes the previous stage's full
+// output in one pass over the whole file -- and restructuring them into
+// a general incremental architecture is a much larger rewrite than this
+// crate's size and one-file-at-a-time design warrant. this cache still
+// eliminates the redundant re-lex/re-parse/re-lint that motivated the
+// request in the first place: unchanged content seen again by an lsp
+// buffer or daemon connection is never recomputed
+
+use crate::config::LintConfig;
+use crate::LintResult;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+
+/// memoized `lint_source` results, one per key
+#[derive(Default)]
+pub struct QueryCache {
+    entries: Mutex<HashMap<String, (u64, LintResult)>>,
+}
+
+impl QueryCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `lint_source(source, config)`, memoized per `key` (typically a
+    /// file path or lsp document uri). a hit requires both `key` and the
+    /// fingerprint of `(source, config)` to match the last call for that
+    /// key, so an edited buffer naturally invalidates its own entry
+    /// rather than needing an explicit invalidation call
+    pub fn lint(&self, key: &str, source: &str, config: &LintConfig) -> LintResult {
+        let fingerprint = fingerprint(source, config);
+        let mut entries = self.entries.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some((cached_fingerprint, cached_result)) = entries.get(key) {
+            if *cached_fingerprint == fingerprint {
+                return cached_result.clone();
+            }
+        }
+        let result = crate::lint_source(source, config);
+        entries.insert(key.to_string(), (fingerprint, result.clone()));
+        result
+    }
+}
+
+fn fingerprint(source: &str, config: &LintConfig) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    source.hash(&mut hasher);
+    // `LintConfig` has no `Hash` impl, but its `Serialize` output is a
+    // stable, complete stand-in for one
+    serde_json::to_string(config).unwrap_or_default().hash(&mut hasher);
+    hasher.finish()
+}
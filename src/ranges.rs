@@ -0,0 +1,223 @@
+// ranges: a small abstract-interpretation pass tracking integer value
+// ranges for loop counters, used to warn when a loop's exit guard checks
+// for a value the counter can never reach
+//
+// lolcode as this parser accepts it has no TIL/WILE loop clause (see the
+// `cfg` module's doc comment) -- a counted loop here is idiomatically
+// written as an unconditional `IM IN YR LOOP` that steps a counter by a
+// constant amount and GTFOs once an `O RLY?` guard trips. the guard's
+// condition itself isn't stored on `Statement::ORly` -- lolcode evaluates
+// it as a standalone expression that sets the implicit `IT` variable,
+// which shows up here as the `Statement::Expr` immediately before the
+// `O RLY?` (see `examples/mixed_issues.lol`'s `BOTH SAEM 3 AN 3` / `O RLY?`
+// pair). this pass looks for exactly that shape and reports when the
+// counter's step direction moves it away from the guard's constant target
+// instead of toward it
+
+use crate::ast::{Block, Expression, Position, Statement};
+use crate::diagnostic::{Diagnostic, Span};
+use std::collections::HashMap;
+
+/// examines a loop `body` for the idiomatic counted-loop shape above and
+/// returns a diagnostic for every guard whose target the counter's step
+/// direction can never reach. `initial` holds counters' known constant
+/// NUMBR values on entry to the loop (from the caller's own constant
+/// propagation)
+pub fn check_unreachable_guards(body: &Block, initial: &HashMap<String, i64>) -> Vec<Diagnostic> {
+    let mut steps = HashMap::new();
+    collect_steps(body, &mut steps);
+
+    let mut diagnostics = Vec::new();
+    collect_guards(body, &steps, initial, &mut diagnostics);
+    diagnostics
+}
+
+/// records the net per-iteration step for every variable reassigned to
+/// `SUM OF`/`DIFF OF` itself and a constant anywhere in `body`, including
+/// inside its own `O RLY?` branches, since those run once per iteration
+/// too; a variable stepped by more than one distinct amount has no single
+/// direction to reason about and is dropped (recorded as a zero step)
+fn collect_steps(body: &Block, steps: &mut HashMap<String, i64>) {
+    for stmt in &body.statements {
+        match stmt {
+            Statement::Assignment { name, value: Some(expr), .. } => {
+                if let Some(amount) = self_step_amount(name, expr) {
+                    steps
+                        .entry(name.clone())
+                        .and_modify(|existing| {
+                            if *existing != amount {
+                                *existing = 0;
+                            }
+                        })
+                        .or_insert(amount);
+                }
+            }
+            Statement::ORly { ya_rly, no_wai, .. } => {
+                collect_steps(ya_rly, steps);
+                if let Some(block) = no_wai {
+                    collect_steps(block, steps);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// the signed constant amount `name` is stepped by if `expr` is exactly
+/// `SUM OF name AN k` or `DIFF OF name AN k`
+fn self_step_amount(name: &str, expr: &Expression) -> Option<i64> {
+    match expr {
+        Expression::Sum { left, right, .. } => step_amount(name, left, right, 1),
+        Expression::Diff { left, right, .. } => step_amount(name, left, right, -1),
+        _ => None,
+    }
+}
+
+fn step_amount(name: &str, left: &Expression, right: &Expression, sign: i64) -> Option<i64> {
+    let Expression::Identifier(lhs, _) = left else {
+        return None;
+    };
+    if lhs != name {
+        return None;
+    }
+    let Expression::Number(n, _) = right else {
+        return None;
+    };
+    n.parse::<i64>().ok().map(|k| sign * k)
+}
+
+/// walks `body` for a bare expression statement immediately followed by an
+/// `O RLY?` whose `YA RLY` branch starts with `GTFO` -- the shape lolcode's
+/// implicit-`IT` condition takes here -- and reports when the expression
+/// compares a stepped counter against a constant target the step direction
+/// can never reach
+fn collect_guards(
+    body: &Block,
+    steps: &HashMap<String, i64>,
+    initial: &HashMap<String, i64>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    for (i, stmt) in body.statements.iter().enumerate() {
+        let Statement::ORly { ya_rly, no_wai, pos, .. } = stmt else {
+            continue;
+        };
+
+        let condition = i
+            .checked_sub(1)
+            .and_then(|j| body.statements.get(j))
+            .and_then(|prev| match prev {
+                Statement::Expr { expression, .. } => Some(expression),
+                _ => None,
+            });
+
+        if let (Some(expression), Some(Statement::Gtfo { .. })) = (condition, ya_rly.statements.first()) {
+            if let Some(diagnostic) = check_condition(expression, pos, steps, initial) {
+                diagnostics.push(diagnostic);
+            }
+        }
+
+        collect_guards(ya_rly, steps, initial, diagnostics);
+        if let Some(block) = no_wai {
+            collect_guards(block, steps, initial, diagnostics);
+        }
+    }
+}
+
+/// if `expression` is `BOTH SAEM counter AN k` for a counter this loop
+/// steps by a known constant amount, returns a diagnostic when, starting
+/// from `initial` and moving by that step, the counter can never land
+/// exactly on `k`
+fn check_condition(
+    expression: &Expression,
+    pos: &Position,
+    steps: &HashMap<String, i64>,
+    initial: &HashMap<String, i64>,
+) -> Option<Diagnostic> {
+    let Expression::BothSaem { left, right, .. } = expression else {
+        return None;
+    };
+    let (name, target) = counter_and_target(left, right)?;
+    let step = *steps.get(&name).filter(|&&s| s != 0)?;
+    let start = *initial.get(&name)?;
+
+    if can_reach(start, step, target) {
+        return None;
+    }
+
+    Some(Diagnostic::warning(
+        Some("LL024"),
+        format!(
+            "warning: loop counter '{}' starts at {} and is stepped by {} each iteration, so it can never equal the exit guard's target {} (line {}, column {})",
+            name, start, step, target, pos.line, pos.column
+        ),
+        Some(Span::from_position(pos)),
+    ))
+}
+
+fn counter_and_target(left: &Expression, right: &Expression) -> Option<(String, i64)> {
+    match (left, right) {
+        (Expression::Identifier(name, _), Expression::Number(n, _)) => {
+            n.parse::<i64>().ok().map(|target| (name.clone(), target))
+        }
+        (Expression::Number(n, _), Expression::Identifier(name, _)) => {
+            n.parse::<i64>().ok().map(|target| (name.clone(), target))
+        }
+        _ => None,
+    }
+}
+
+/// true if repeatedly adding `step` to `start` ever lands exactly on
+/// `target` -- the distance between them is covered by a whole, positive
+/// number of steps in `step`'s own direction
+fn can_reach(start: i64, step: i64, target: i64) -> bool {
+    let distance = target - start;
+    distance == 0 || (distance.signum() == step.signum() && distance % step == 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::Lexer;
+    use crate::parser::Parser;
+
+    fn loop_body(source: &str) -> Block {
+        let tokens = Lexer::new(source.to_string()).tokenize();
+        let program = Parser::new(tokens).parse_program();
+        match program.body.statements.into_iter().next() {
+            Some(Statement::Loop { body, .. }) => body,
+            other => panic!("expected a single top-level loop, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn warns_when_the_step_direction_overshoots_the_target() {
+        // i starts at 0 and counts up by 2, so it steps 0, 2, 4, 6... and
+        // can never land exactly on the odd target 5
+        let body = loop_body(
+            "IM IN YR LOOP\n    i R SUM OF i AN 2\n    BOTH SAEM i AN 5\n    O RLY?\n        YA RLY\n            GTFO\n    OIC\nIM OUTTA YR LOOP\n",
+        );
+        let mut initial = HashMap::new();
+        initial.insert("i".to_string(), 0);
+        let diagnostics = check_unreachable_guards(&body, &initial);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, Some("LL024"));
+    }
+
+    #[test]
+    fn does_not_warn_when_the_counter_can_reach_the_target() {
+        let body = loop_body(
+            "IM IN YR LOOP\n    i R SUM OF i AN 1\n    BOTH SAEM i AN 3\n    O RLY?\n        YA RLY\n            GTFO\n    OIC\nIM OUTTA YR LOOP\n",
+        );
+        let mut initial = HashMap::new();
+        initial.insert("i".to_string(), 0);
+        assert!(check_unreachable_guards(&body, &initial).is_empty());
+    }
+
+    #[test]
+    fn can_reach_handles_both_step_directions_and_the_zero_distance_case() {
+        assert!(can_reach(0, 2, 4));
+        assert!(!can_reach(0, 2, 5));
+        assert!(can_reach(10, -3, 1));
+        assert!(can_reach(5, 1, 5));
+    }
+}
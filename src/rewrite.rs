@@ -0,0 +1,179 @@
+// rewrite: the `lol-lint rewrite --rules <rules.toml> <file.lol>...` cli
+// entry point for `codemod`'s pattern-match-and-replace engine -- large
+// mechanical edits (a deprecated construct swapped for its replacement
+// across a whole codebase) described declaratively instead of as a
+// one-off script
+//
+// a rules file is a list of `[[rule]]` tables, each a `match` pattern
+// and a `replace` template, in the same toml shape `hierconfig` already
+// uses for per-directory config overrides:
+//
+//   [[rule]]
+//   match = "QUOSHUNT OF $x AN 0"
+//   replace = "0"
+//
+// rules apply in file order, each one re-lexing/re-parsing the file
+// before scanning for matches, since the previous rule's edits shift
+// every byte offset after them
+
+use lol_lint::ast::Expression;
+use lol_lint::codemod;
+use lol_lint::lexer::Lexer;
+use lol_lint::parser::Parser;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[derive(Deserialize)]
+struct RulesFile {
+    rule: Vec<RawRule>,
+}
+
+#[derive(Deserialize)]
+struct RawRule {
+    #[serde(rename = "match")]
+    pattern: String,
+    replace: String,
+}
+
+struct Rule {
+    pattern: Expression,
+    replace: Expression,
+    /// kept for error messages -- `Expression` doesn't carry the
+    /// original pattern text back out once parsed
+    source: String,
+}
+
+pub fn run(args: &[String]) -> i32 {
+    let Some(rules_path) = flag_value(args, "--rules") else {
+        eprintln!("usage: lol-lint rewrite --rules <rules.toml> <file.lol>...");
+        return 2;
+    };
+
+    let files: Vec<&String> = {
+        let mut files = Vec::new();
+        let mut skip_next = false;
+        for arg in args {
+            if skip_next {
+                skip_next = false;
+                continue;
+            }
+            if arg == "--rules" {
+                skip_next = true;
+                continue;
+            }
+            files.push(arg);
+        }
+        files
+    };
+
+    if files.is_empty() {
+        eprintln!("usage: lol-lint rewrite --rules <rules.toml> <file.lol>...");
+        return 2;
+    }
+
+    let rules_text = match std::fs::read_to_string(rules_path) {
+        Ok(text) => text,
+        Err(e) => {
+            eprintln!("error: could not read '{rules_path}': {e}");
+            return 2;
+        }
+    };
+    let rules_file: RulesFile = match toml::from_str(&rules_text) {
+        Ok(rules) => rules,
+        Err(e) => {
+            eprintln!("error: could not parse '{rules_path}': {e}");
+            return 2;
+        }
+    };
+
+    let mut rules = Vec::new();
+    for raw in rules_file.rule {
+        let pattern = match codemod::parse_pattern(&raw.pattern) {
+            Ok(pattern) => pattern,
+            Err(e) => {
+                eprintln!("error: bad match pattern '{}': {e}", raw.pattern);
+                return 2;
+            }
+        };
+        let replace = match codemod::parse_pattern(&raw.replace) {
+            Ok(replace) => replace,
+            Err(e) => {
+                eprintln!("error: bad replace template '{}': {e}", raw.replace);
+                return 2;
+            }
+        };
+        rules.push(Rule { pattern, replace, source: raw.pattern });
+    }
+
+    let mut had_error = false;
+    for file in files {
+        match rewrite_file(file, &rules) {
+            Ok(count) => {
+                if count > 0 {
+                    println!("rewrote {count} match(es) in {file}");
+                }
+            }
+            Err(e) => {
+                eprintln!("error: {e}");
+                had_error = true;
+            }
+        }
+    }
+
+    if had_error {
+        2
+    } else {
+        0
+    }
+}
+
+fn flag_value<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    args.iter().position(|a| a == name).and_then(|i| args.get(i + 1)).map(String::as_str)
+}
+
+/// applies every rule to `file` in order, writing the result back if
+/// anything changed; returns the total number of matches rewritten
+fn rewrite_file(file: &str, rules: &[Rule]) -> Result<usize, String> {
+    let mut source = std::fs::read_to_string(file).map_err(|e| format!("could not read '{file}': {e}"))?;
+    let mut total = 0;
+
+    for rule in rules {
+        loop {
+            let tokens = Lexer::new(source.clone()).tokenize();
+            let mut parser = Parser::new(tokens);
+            let program = parser.parse_program();
+
+            let mut found: Option<(usize, usize, HashMap<String, String>)> = None;
+            codemod::visit_expressions(&program, &mut |candidate| {
+                if found.is_some() {
+                    return;
+                }
+                if let Some(bindings) = codemod::match_pattern(&rule.pattern, candidate) {
+                    let (start, end) = codemod::expr_span(candidate);
+                    let bindings_text: HashMap<String, String> = bindings
+                        .into_iter()
+                        .map(|(name, expr)| {
+                            let (bstart, bend) = codemod::expr_span(expr);
+                            (name, source[bstart..bend].to_string())
+                        })
+                        .collect();
+                    found = Some((start, end, bindings_text));
+                }
+            });
+
+            let Some((start, end, bindings_text)) = found else {
+                break;
+            };
+            let replacement = codemod::render_replacement(&rule.replace, &bindings_text)
+                .map_err(|e| format!("rule '{}': {e}", rule.source))?;
+
+            source = format!("{}{}{}", &source[..start], replacement, &source[end..]);
+            total += 1;
+        }
+    }
+
+    if total > 0 {
+        std::fs::write(file, &source).map_err(|e| format!("could not write '{file}': {e}"))?;
+    }
+    Ok(total)
+}
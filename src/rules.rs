@@ -0,0 +1,275 @@
+// rules: static registry describing every lint check lol-lint can run,
+// used by `--list-rules` so editors and docs don't have to scrape source
+
+use serde::Serialize;
+
+/// severity a rule reports at by default
+#[derive(Serialize, Clone, Copy)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Severity {
+    fn as_str(self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        }
+    }
+}
+
+/// one entry in the rule registry
+#[derive(Serialize)]
+pub struct Rule {
+    pub code: &'static str,
+    pub name: &'static str,
+    pub severity_str: &'static str,
+    pub fixable: bool,
+    pub summary: &'static str,
+}
+
+macro_rules! rule {
+    ($code:expr, $name:expr, $severity:expr, $fixable:expr, $summary:expr) => {
+        Rule {
+            code: $code,
+            name: $name,
+            severity_str: $severity.as_str(),
+            fixable: $fixable,
+            summary: $summary,
+        }
+    };
+}
+
+/// every rule lol-lint currently implements, always on unless noted
+pub fn all() -> Vec<Rule> {
+    vec![
+        rule!(
+            "LL001",
+            "double-declaration",
+            Severity::Error,
+            true,
+            "a variable is declared more than once"
+        ),
+        rule!(
+            "LL002",
+            "undeclared-variable",
+            Severity::Error,
+            false,
+            "a variable is read or assigned before it is declared"
+        ),
+        rule!(
+            "LL003",
+            "reserved-name",
+            Severity::Error,
+            false,
+            "assignment to a reserved name (IT, WIN, FAIL)"
+        ),
+        rule!(
+            "LL004",
+            "unused-variable",
+            Severity::Warning,
+            true,
+            "a declared variable is never read"
+        ),
+        rule!(
+            "LL005",
+            "numbar-equality",
+            Severity::Warning,
+            false,
+            "BOTH SAEM compares NUMBAR values, which is rarely exact"
+        ),
+        rule!(
+            "LL006",
+            "numbr-overflow",
+            Severity::Warning,
+            false,
+            "a constant NUMBR expression overflows 32 bits"
+        ),
+        rule!(
+            "LL007",
+            "empty-loop-body",
+            Severity::Warning,
+            false,
+            "a loop has no statements in its body"
+        ),
+        rule!(
+            "LL008",
+            "loop-exits-first-iteration",
+            Severity::Warning,
+            false,
+            "a loop's first statement is GTFO, so it never repeats"
+        ),
+        rule!(
+            "LL009",
+            "missing-no-wai",
+            Severity::Warning,
+            true,
+            "an O RLY? block has no NO WAI branch"
+        ),
+        rule!(
+            "LL010",
+            "empty-conditional-branch",
+            Severity::Warning,
+            false,
+            "a YA RLY or NO WAI branch has no statements"
+        ),
+        rule!(
+            "LL011",
+            "noob-usage",
+            Severity::Warning,
+            false,
+            "VISIBLE of a variable that may still be NOOB (uninitialized)"
+        ),
+        rule!(
+            "LL012",
+            "unused-gimmeh-input",
+            Severity::Warning,
+            false,
+            "GIMMEH read a variable that is never used"
+        ),
+        rule!(
+            "LL013",
+            "tainted-arithmetic",
+            Severity::Warning,
+            false,
+            "raw GIMMEH input used directly in arithmetic"
+        ),
+        rule!(
+            "LL014",
+            "identifier-length",
+            Severity::Warning,
+            false,
+            "opt-in: identifier shorter or longer than the configured bounds"
+        ),
+        rule!(
+            "LL015",
+            "keyword-like-identifier",
+            Severity::Warning,
+            false,
+            "an identifier is spelled like a LOLCODE keyword"
+        ),
+        rule!(
+            "LL016",
+            "whitespace-style",
+            Severity::Warning,
+            false,
+            "opt-in: trailing whitespace or mixed tab/space indentation"
+        ),
+        rule!(
+            "LL017",
+            "missing-final-newline",
+            Severity::Warning,
+            true,
+            "opt-in: the file does not end with a newline"
+        ),
+        rule!(
+            "LL018",
+            "comment-density",
+            Severity::Warning,
+            false,
+            "opt-in: comment-to-code ratio falls below the configured minimum"
+        ),
+        rule!(
+            "LL019",
+            "declarations-at-top",
+            Severity::Warning,
+            false,
+            "opt-in: a declaration appears after a non-declaration statement"
+        ),
+        rule!(
+            "LL020",
+            "unused-suppression",
+            Severity::Warning,
+            false,
+            "a disable-next-line comment suppressed nothing"
+        ),
+        rule!(
+            "LL021",
+            "constant-condition",
+            Severity::Warning,
+            false,
+            "a BOTH SAEM or DIFFRINT compares two literals with a fixed outcome"
+        ),
+        rule!(
+            "LL022",
+            "keyword-casing",
+            Severity::Warning,
+            true,
+            "a lolcode keyword (3+ letters) is written in the wrong case"
+        ),
+        rule!(
+            "LL023",
+            "division-by-zero",
+            Severity::Warning,
+            false,
+            "a QUOSHUNT OF or MOD OF divides by a known constant zero, including through a propagated variable"
+        ),
+        rule!(
+            "LL024",
+            "unreachable-loop-guard",
+            Severity::Warning,
+            false,
+            "a loop's exit guard checks a counter for a value its step direction can never reach"
+        ),
+    ]
+}
+
+/// prints the full detail for one rule code (as looked up by
+/// `lol-lint explain <RULE_CODE>`) and returns the process exit code: 0 if
+/// the code was found, 2 if it wasn't
+pub fn explain(code: &str) -> i32 {
+    let Some(rule) = all().into_iter().find(|r| r.code.eq_ignore_ascii_case(code)) else {
+        eprintln!("error: no such rule '{}'", code);
+        eprintln!("run `lol-lint --list-rules` to see all rule codes");
+        return 2;
+    };
+
+    println!("{} ({})", rule.code, rule.name);
+    println!("severity: {}", rule.severity_str);
+    println!("fixable:  {}", rule.fixable);
+    println!();
+    println!("{}", rule.summary);
+    0
+}
+
+/// best-effort classification of a diagnostic message back to the rule
+/// code that produced it, by matching the wording each check uses;
+/// diagnostics remain plain strings for now, so this is textual rather
+/// than tagged at the source, and returns `None` for unrecognized text
+pub fn classify(message: &str) -> Option<&'static str> {
+    const PATTERNS: &[(&str, &str)] = &[
+        ("declared twice", "LL001"),
+        ("undeclared variable", "LL002"),
+        ("cannot assign to reserved name", "LL003"),
+        ("declared but never used", "LL004"),
+        ("compares a NUMBAR", "LL005"),
+        ("overflows lolcode's NUMBR range", "LL006"),
+        ("empty loop body", "LL007"),
+        ("loop always exits on the first iteration", "LL008"),
+        ("O RLY? without NO WAI branch", "LL009"),
+        ("YA RLY block is empty", "LL010"),
+        ("may still be NOOB", "LL011"),
+        ("received GIMMEH input but is never used", "LL012"),
+        ("holds raw GIMMEH input", "LL013"),
+        ("shorter than the minimum length", "LL014"),
+        ("longer than the maximum length", "LL014"),
+        ("collides with a lolcode keyword", "LL015"),
+        ("trailing whitespace", "LL016"),
+        ("mixes tabs and spaces", "LL016"),
+        ("missing a final newline", "LL017"),
+        ("comment density is", "LL018"),
+        ("declaration appears after other statements", "LL019"),
+        ("unused lint suppression comment", "LL020"),
+        ("is always true", "LL021"),
+        ("is always false", "LL021"),
+        ("should be written as the keyword", "LL022"),
+        ("divides by a constant zero", "LL023"),
+        ("can never equal the exit guard's target", "LL024"),
+    ];
+
+    PATTERNS
+        .iter()
+        .find(|(needle, _)| message.contains(needle))
+        .map(|(_, code)| *code)
+}
@@ -0,0 +1,193 @@
+// symbols: a public symbol table over the ast, recording every variable
+// definition and reference together with its source span
+//
+// this is distinct from the linter's own `declared_vars`/`used_vars`
+// bookkeeping, which exists only to drive its diagnostics and is thrown
+// away once linting finishes. `SymbolTable` is the reusable data other
+// tools built on this library -- rename, goto-definition, did-you-mean
+// suggestions against a misspelled name -- need but have no reason to
+// recompute from the ast themselves
+
+use crate::ast::{Block, Expression, Position, Program, Statement};
+use serde::Serialize;
+
+/// what kind of occurrence of a name a `Use` records
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Occurrence {
+    /// bound by `I HAS A`
+    Definition,
+    /// a later `x R ...` assignment
+    Assignment,
+    /// bound by `GIMMEH`, reading raw input
+    Input,
+    /// read, e.g. as an identifier expression
+    Reference,
+}
+
+/// one occurrence of a symbol's name at a source position
+#[derive(Debug, Clone, Serialize)]
+pub struct Use {
+    pub kind: Occurrence,
+    pub pos: Position,
+}
+
+/// every occurrence recorded for one variable name, in the order they
+/// appear in the program
+#[derive(Debug, Clone, Serialize)]
+pub struct Symbol {
+    pub name: String,
+    pub uses: Vec<Use>,
+}
+
+impl Symbol {
+    /// the position of this symbol's `I HAS A` declaration, if any was
+    /// recorded -- a use of an undeclared name has no `Definition` entry
+    pub fn definition(&self) -> Option<&Position> {
+        self.uses
+            .iter()
+            .find(|u| u.kind == Occurrence::Definition)
+            .map(|u| &u.pos)
+    }
+}
+
+/// a program's full symbol table, keyed by variable name in first-seen order
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct SymbolTable {
+    symbols: Vec<Symbol>,
+}
+
+impl SymbolTable {
+    /// walks `program` recording every declaration, assignment, GIMMEH, and
+    /// read of a variable name, in source order
+    pub fn build(program: &Program) -> Self {
+        let mut table = SymbolTable::default();
+        table.walk_block(&program.body);
+        table
+    }
+
+    /// all symbols, in the order their first occurrence appeared in the source
+    pub fn symbols(&self) -> &[Symbol] {
+        &self.symbols
+    }
+
+    /// looks up a symbol by name
+    pub fn get(&self, name: &str) -> Option<&Symbol> {
+        self.symbols.iter().find(|s| s.name == name)
+    }
+
+    /// every distinct name that occurs anywhere in the program, for
+    /// did-you-mean style suggestions against a name that doesn't resolve
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.symbols.iter().map(|s| s.name.as_str())
+    }
+
+    fn record(&mut self, name: &str, kind: Occurrence, pos: &Position) {
+        let use_ = Use { kind, pos: pos.clone() };
+        match self.symbols.iter_mut().find(|s| s.name == name) {
+            Some(symbol) => symbol.uses.push(use_),
+            None => self.symbols.push(Symbol { name: name.to_string(), uses: vec![use_] }),
+        }
+    }
+
+    fn walk_block(&mut self, block: &Block) {
+        for stmt in &block.statements {
+            self.walk_statement(stmt);
+        }
+    }
+
+    fn walk_statement(&mut self, stmt: &Statement) {
+        match stmt {
+            Statement::Declaration { name, value, pos } => {
+                self.record(name, Occurrence::Definition, pos);
+                if let Some(expr) = value {
+                    self.walk_expression(expr);
+                }
+            }
+            Statement::Assignment { name, value, pos } => {
+                self.record(name, Occurrence::Assignment, pos);
+                if let Some(expr) = value {
+                    self.walk_expression(expr);
+                }
+            }
+            Statement::Gimmeh { name, pos } => {
+                self.record(name, Occurrence::Input, pos);
+            }
+            Statement::Visible { expressions, .. } => {
+                for expr in expressions {
+                    self.walk_expression(expr);
+                }
+            }
+            Statement::ORly { ya_rly, no_wai, .. } => {
+                self.walk_block(ya_rly);
+                if let Some(block) = no_wai {
+                    self.walk_block(block);
+                }
+            }
+            Statement::Loop { body, .. } => self.walk_block(body),
+            Statement::Gtfo { .. } => {}
+            Statement::Expr { expression, .. } => self.walk_expression(expression),
+        }
+    }
+
+    fn walk_expression(&mut self, expr: &Expression) {
+        match expr {
+            Expression::Identifier(name, pos) => self.record(name, Occurrence::Reference, pos),
+            Expression::Number(_, _) | Expression::String(_, _) => {}
+            Expression::Sum { left, right, .. }
+            | Expression::Diff { left, right, .. }
+            | Expression::Produkt { left, right, .. }
+            | Expression::Quoshunt { left, right, .. }
+            | Expression::Mod { left, right, .. }
+            | Expression::BothSaem { left, right, .. }
+            | Expression::Diffrint { left, right, .. } => {
+                self.walk_expression(left);
+                self.walk_expression(right);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::Lexer;
+    use crate::parser::Parser;
+
+    fn build(source: &str) -> SymbolTable {
+        let tokens = Lexer::new(source.to_string()).tokenize();
+        let program = Parser::new(tokens).parse_program();
+        SymbolTable::build(&program)
+    }
+
+    #[test]
+    fn records_a_declaration_then_a_reference_in_source_order() {
+        let table = build("HAI 1.2\nI HAS A x ITZ 1\nVISIBLE x\nKTHXBYE\n");
+        let symbol = table.get("x").expect("x should be recorded");
+        assert_eq!(symbol.uses.len(), 2);
+        assert_eq!(symbol.uses[0].kind, Occurrence::Definition);
+        assert_eq!(symbol.uses[1].kind, Occurrence::Reference);
+    }
+
+    #[test]
+    fn a_gimmeh_read_is_recorded_as_input_not_a_definition() {
+        let table = build("HAI 1.2\nI HAS A x\nGIMMEH x\nKTHXBYE\n");
+        let symbol = table.get("x").unwrap();
+        assert!(symbol.uses.iter().any(|u| u.kind == Occurrence::Input));
+    }
+
+    #[test]
+    fn an_undeclared_name_has_no_definition_position() {
+        let table = build("HAI 1.2\nVISIBLE x\nKTHXBYE\n");
+        let symbol = table.get("x").unwrap();
+        assert!(symbol.definition().is_none());
+    }
+
+    #[test]
+    fn names_and_symbols_walk_nested_o_rly_and_loop_bodies() {
+        let table = build(
+            "HAI 1.2\nBOTH SAEM 1 AN 1\nO RLY?\n    YA RLY\n        I HAS A y ITZ 2\nOIC\nIM IN YR LOOP\n    VISIBLE y\nIM OUTTA YR LOOP\nKTHXBYE\n",
+        );
+        assert!(table.names().any(|n| n == "y"));
+        assert_eq!(table.symbols().len(), 1);
+    }
+}
@@ -0,0 +1,434 @@
+// transpile: lowers the ast into readable python or javascript, backed
+// by a small runtime shim that reproduces this crate's own numeric
+// promotion and stringification rules (see `interpreter`'s `Value`,
+// which this mirrors) -- for migrating a legacy lolcode script off this
+// language entirely rather than just linting or running it in place
+//
+// `HOW DUZ`/`FOUND YR`/`I IZ` have no ast representation here (the same
+// gap `interpreter`'s doc comment describes), so there's nothing to
+// transpile them from; anything the parser itself can't make sense of
+// is already reported as a parse error before transpilation is attempted,
+// which doubles as this command's "unsupported construct" diagnostic
+
+use lol_lint::ast::{Block, Expression, Program, Statement};
+use lol_lint::lexer::Lexer;
+use lol_lint::parser::Parser;
+
+#[derive(Clone, Copy)]
+enum Target {
+    Python,
+    JavaScript,
+}
+
+pub fn run(args: &[String]) -> i32 {
+    let target = match flag_value(args, "--target") {
+        Some("python" | "py") => Target::Python,
+        Some("javascript" | "js") => Target::JavaScript,
+        Some(other) => {
+            eprintln!("error: unknown --target '{other}' (expected 'python' or 'javascript')");
+            return 2;
+        }
+        None => {
+            eprintln!("usage: lol-lint transpile --target <python|javascript> <file.lol>");
+            return 2;
+        }
+    };
+
+    let mut files = Vec::new();
+    let mut skip_next = false;
+    for arg in args {
+        if skip_next {
+            skip_next = false;
+            continue;
+        }
+        if arg == "--target" {
+            skip_next = true;
+            continue;
+        }
+        files.push(arg);
+    }
+
+    let Some(file) = files.first() else {
+        eprintln!("usage: lol-lint transpile --target <python|javascript> <file.lol>");
+        return 2;
+    };
+
+    let source = match std::fs::read_to_string(file) {
+        Ok(source) => source,
+        Err(e) => {
+            eprintln!("error: could not read '{file}': {e}");
+            return 2;
+        }
+    };
+
+    let tokens = Lexer::new(source).tokenize();
+    let mut parser = Parser::new(tokens);
+    let program = parser.parse_program();
+    if !parser.errors.is_empty() {
+        for error in &parser.errors {
+            eprintln!("error: {}", error.message);
+        }
+        return 2;
+    }
+
+    print!("{}", transpile(&program, target));
+    0
+}
+
+fn flag_value<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    args.iter().position(|a| a == name).and_then(|i| args.get(i + 1)).map(String::as_str)
+}
+
+fn transpile(program: &Program, target: Target) -> String {
+    let mut out = match target {
+        Target::Python => PYTHON_SHIM.to_string(),
+        Target::JavaScript => JS_SHIM.to_string(),
+    };
+    // `IT` is implicit in lolcode -- every program has it, whether or not
+    // it ever appears in source -- so it needs a binding up front rather
+    // than only coming into existence at its first `Expr` statement
+    match target {
+        Target::Python => {
+            out.push_str("IT = None\n\n");
+            block_py(&program.body, 0, &mut out);
+        }
+        Target::JavaScript => {
+            out.push_str("let IT = null;\n\n");
+            block_js(&program.body, 0, &mut out);
+        }
+    }
+    out
+}
+
+const PYTHON_SHIM: &str = r#"# generated by `lol-lint transpile --target python`
+def _lol_is_numbar(v):
+    if isinstance(v, float):
+        return True
+    if isinstance(v, str):
+        try:
+            return "." in v and not isinstance(float(v), type(None))
+        except ValueError:
+            return False
+    return False
+
+def _lol_numbar(v):
+    if isinstance(v, bool):
+        return 1.0 if v else 0.0
+    if isinstance(v, str):
+        try:
+            return float(v)
+        except ValueError:
+            return 0.0
+    return float(v) if v is not None else 0.0
+
+def _lol_numbr(v):
+    if isinstance(v, bool):
+        return 1 if v else 0
+    if isinstance(v, str):
+        try:
+            return int(v)
+        except ValueError:
+            return 0
+    return int(v) if v is not None else 0
+
+def _lol_binop(a, b, op_i, op_f):
+    if _lol_is_numbar(a) or _lol_is_numbar(b):
+        return op_f(_lol_numbar(a), _lol_numbar(b))
+    return op_i(_lol_numbr(a), _lol_numbr(b))
+
+# python's // and % both floor toward negative infinity, but lolcode's
+# NUMBR division truncates toward zero (the same as rust's own `/`/`%`,
+# which is what `interpreter.rs` runs QUOSHUNT OF/MOD OF through), so
+# integer division needs its own helpers rather than python's operators
+def _lol_idiv(a, b):
+    q = abs(a) // abs(b)
+    return -q if (a < 0) != (b < 0) else q
+
+def _lol_imod(a, b):
+    return a - b * _lol_idiv(a, b)
+
+def _lol_same(a, b):
+    if _lol_is_numbar(a) or _lol_is_numbar(b):
+        return abs(_lol_numbar(a) - _lol_numbar(b)) < 1e-9
+    if isinstance(a, int) and not isinstance(a, bool) and isinstance(b, int) and not isinstance(b, bool):
+        return a == b
+    return _lol_str(a) == _lol_str(b)
+
+def _lol_str(v):
+    if v is None:
+        return ""
+    if isinstance(v, bool):
+        return "WIN" if v else "FAIL"
+    if isinstance(v, float):
+        return f"{v:.2f}"
+    return str(v)
+
+def _lol_truthy(v):
+    if v is None:
+        return False
+    if isinstance(v, str):
+        return len(v) > 0
+    return bool(v)
+
+def _lol_input():
+    try:
+        return input()
+    except EOFError:
+        return ""
+
+"#;
+
+const JS_SHIM: &str = r#"// generated by `lol-lint transpile --target javascript`
+function _lolIsNumbar(v) {
+    if (typeof v === "number" && !Number.isInteger(v)) return true;
+    if (typeof v === "string") return v.includes(".") && !Number.isNaN(parseFloat(v));
+    return false;
+}
+function _lolNumbar(v) {
+    if (typeof v === "boolean") return v ? 1.0 : 0.0;
+    if (typeof v === "string") { const n = parseFloat(v); return Number.isNaN(n) ? 0.0 : n; }
+    return v == null ? 0.0 : Number(v);
+}
+function _lolNumbr(v) {
+    if (typeof v === "boolean") return v ? 1 : 0;
+    if (typeof v === "string") { const n = parseInt(v, 10); return Number.isNaN(n) ? 0 : n; }
+    return v == null ? 0 : Math.trunc(Number(v));
+}
+function _lolBinop(a, b, opI, opF) {
+    if (_lolIsNumbar(a) || _lolIsNumbar(b)) return opF(_lolNumbar(a), _lolNumbar(b));
+    return opI(_lolNumbr(a), _lolNumbr(b));
+}
+function _lolSame(a, b) {
+    if (_lolIsNumbar(a) || _lolIsNumbar(b)) return Math.abs(_lolNumbar(a) - _lolNumbar(b)) < 1e-9;
+    if (Number.isInteger(a) && Number.isInteger(b)) return a === b;
+    return _lolStr(a) === _lolStr(b);
+}
+function _lolStr(v) {
+    if (v == null) return "";
+    if (typeof v === "boolean") return v ? "WIN" : "FAIL";
+    if (typeof v === "number" && !Number.isInteger(v)) return v.toFixed(2);
+    return String(v);
+}
+function _lolTruthy(v) {
+    if (v == null) return false;
+    if (typeof v === "string") return v.length > 0;
+    return Boolean(v);
+}
+
+"#;
+
+fn indent(level: usize) -> String {
+    "    ".repeat(level)
+}
+
+fn block_py(block: &Block, level: usize, out: &mut String) {
+    if block.statements.is_empty() {
+        out.push_str(&format!("{}pass\n", indent(level)));
+        return;
+    }
+    for stmt in &block.statements {
+        stmt_py(stmt, level, out);
+    }
+}
+
+fn stmt_py(stmt: &Statement, level: usize, out: &mut String) {
+    let pad = indent(level);
+    match stmt {
+        Statement::Declaration { name, value, .. } | Statement::Assignment { name, value, .. } => {
+            let value = value.as_ref().map_or("None".to_string(), expr_py);
+            out.push_str(&format!("{pad}{name} = {value}\n"));
+        }
+        Statement::Visible { expressions, .. } => {
+            let parts: Vec<String> = expressions.iter().map(|e| format!("_lol_str({})", expr_py(e))).collect();
+            out.push_str(&format!("{pad}print(\" \".join([{}]))\n", parts.join(", ")));
+        }
+        Statement::ORly { ya_rly, no_wai, .. } => {
+            out.push_str(&format!("{pad}if _lol_truthy(IT):\n"));
+            block_py(ya_rly, level + 1, out);
+            if let Some(no_wai) = no_wai {
+                out.push_str(&format!("{pad}else:\n"));
+                block_py(no_wai, level + 1, out);
+            }
+        }
+        Statement::Loop { body, .. } => {
+            out.push_str(&format!("{pad}while True:\n"));
+            block_py(body, level + 1, out);
+        }
+        Statement::Gtfo { .. } => out.push_str(&format!("{pad}break\n")),
+        Statement::Gimmeh { name, .. } => out.push_str(&format!("{pad}{name} = _lol_input()\n")),
+        Statement::Expr { expression, .. } => {
+            out.push_str(&format!("{pad}IT = {}\n", expr_py(expression)));
+        }
+    }
+}
+
+fn expr_py(expr: &Expression) -> String {
+    match expr {
+        Expression::Number(n, _) if n.contains('.') => n.to_string(),
+        Expression::Number(n, _) => n.to_string(),
+        Expression::String(s, _) => format!("{:?}", s),
+        Expression::Identifier(name, _) => name.clone(),
+        Expression::Sum { left, right, .. } => binop_py("lambda a, b: a + b", "lambda a, b: a + b", left, right),
+        Expression::Diff { left, right, .. } => binop_py("lambda a, b: a - b", "lambda a, b: a - b", left, right),
+        Expression::Produkt { left, right, .. } => {
+            binop_py("lambda a, b: a * b", "lambda a, b: a * b", left, right)
+        }
+        Expression::Quoshunt { left, right, .. } => binop_py(
+            "lambda a, b: _lol_idiv(a, b) if b != 0 else 0",
+            "lambda a, b: a / b if b != 0 else 0",
+            left,
+            right,
+        ),
+        Expression::Mod { left, right, .. } => binop_py(
+            "lambda a, b: _lol_imod(a, b) if b != 0 else 0",
+            "lambda a, b: a % b if b != 0 else 0",
+            left,
+            right,
+        ),
+        Expression::BothSaem { left, right, .. } => {
+            format!("_lol_same({}, {})", expr_py(left), expr_py(right))
+        }
+        Expression::Diffrint { left, right, .. } => {
+            format!("(not _lol_same({}, {}))", expr_py(left), expr_py(right))
+        }
+    }
+}
+
+fn binop_py(op_i: &str, op_f: &str, left: &Expression, right: &Expression) -> String {
+    format!("_lol_binop({}, {}, {op_i}, {op_f})", expr_py(left), expr_py(right))
+}
+
+fn block_js(block: &Block, level: usize, out: &mut String) {
+    for stmt in &block.statements {
+        stmt_js(stmt, level, out);
+    }
+}
+
+fn stmt_js(stmt: &Statement, level: usize, out: &mut String) {
+    let pad = indent(level);
+    match stmt {
+        Statement::Declaration { name, value, .. } => {
+            let value = value.as_ref().map_or("null".to_string(), expr_js);
+            out.push_str(&format!("{pad}let {name} = {value};\n"));
+        }
+        Statement::Assignment { name, value, .. } => {
+            let value = value.as_ref().map_or("null".to_string(), expr_js);
+            out.push_str(&format!("{pad}{name} = {value};\n"));
+        }
+        Statement::Visible { expressions, .. } => {
+            let parts: Vec<String> = expressions.iter().map(|e| format!("_lolStr({})", expr_js(e))).collect();
+            out.push_str(&format!("{pad}console.log([{}].join(\" \"));\n", parts.join(", ")));
+        }
+        Statement::ORly { ya_rly, no_wai, .. } => {
+            out.push_str(&format!("{pad}if (_lolTruthy(IT)) {{\n"));
+            block_js(ya_rly, level + 1, out);
+            if let Some(no_wai) = no_wai {
+                out.push_str(&format!("{pad}}} else {{\n"));
+                block_js(no_wai, level + 1, out);
+            }
+            out.push_str(&format!("{pad}}}\n"));
+        }
+        Statement::Loop { body, .. } => {
+            out.push_str(&format!("{pad}while (true) {{\n"));
+            block_js(body, level + 1, out);
+            out.push_str(&format!("{pad}}}\n"));
+        }
+        Statement::Gtfo { .. } => out.push_str(&format!("{pad}break;\n")),
+        Statement::Gimmeh { name, .. } => {
+            out.push_str(&format!("{pad}{name} = _lolInput();\n"));
+        }
+        Statement::Expr { expression, .. } => {
+            out.push_str(&format!("{pad}IT = {};\n", expr_js(expression)));
+        }
+    }
+}
+
+fn expr_js(expr: &Expression) -> String {
+    match expr {
+        Expression::Number(n, _) => n.to_string(),
+        Expression::String(s, _) => format!("{:?}", s),
+        Expression::Identifier(name, _) => name.clone(),
+        Expression::Sum { left, right, .. } => binop_js("(a, b) => a + b", "(a, b) => a + b", left, right),
+        Expression::Diff { left, right, .. } => binop_js("(a, b) => a - b", "(a, b) => a - b", left, right),
+        Expression::Produkt { left, right, .. } => binop_js("(a, b) => a * b", "(a, b) => a * b", left, right),
+        Expression::Quoshunt { left, right, .. } => binop_js(
+            "(a, b) => (b !== 0 ? Math.trunc(a / b) : 0)",
+            "(a, b) => (b !== 0 ? a / b : 0)",
+            left,
+            right,
+        ),
+        // js's `%` already truncates toward zero like rust's, so both the
+        // int and float branches can share it, same as `interpreter.rs`
+        Expression::Mod { left, right, .. } => binop_js(
+            "(a, b) => (b !== 0 ? a % b : 0)",
+            "(a, b) => (b !== 0 ? a % b : 0)",
+            left,
+            right,
+        ),
+        Expression::BothSaem { left, right, .. } => {
+            format!("_lolSame({}, {})", expr_js(left), expr_js(right))
+        }
+        Expression::Diffrint { left, right, .. } => {
+            format!("(!_lolSame({}, {}))", expr_js(left), expr_js(right))
+        }
+    }
+}
+
+fn binop_js(op_i: &str, op_f: &str, left: &Expression, right: &Expression) -> String {
+    format!("_lolBinop({}, {}, {op_i}, {op_f})", expr_js(left), expr_js(right))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(source: &str) -> Program {
+        let tokens = Lexer::new(source.to_string()).tokenize();
+        Parser::new(tokens).parse_program()
+    }
+
+    #[test]
+    fn python_quoshunt_uses_a_distinct_truncating_int_lambda() {
+        let program = parse("HAI 1.2\nVISIBLE QUOSHUNT OF 7 AN 2\nKTHXBYE\n");
+        let out = transpile(&program, Target::Python);
+        // the int and float branches must no longer be the same lambda,
+        // or QUOSHUNT/MOD would always run through float division
+        assert!(out.contains("_lol_idiv(a, b) if b != 0 else 0"));
+        assert!(out.contains("a / b if b != 0 else 0"));
+    }
+
+    #[test]
+    fn python_mod_uses_a_distinct_truncating_int_lambda() {
+        let program = parse("HAI 1.2\nVISIBLE MOD OF 7 AN 2\nKTHXBYE\n");
+        let out = transpile(&program, Target::Python);
+        assert!(out.contains("_lol_imod(a, b) if b != 0 else 0"));
+    }
+
+    #[test]
+    fn javascript_quoshunt_truncates_the_int_branch_toward_zero() {
+        let program = parse("HAI 1.2\nVISIBLE QUOSHUNT OF 7 AN 2\nKTHXBYE\n");
+        let out = transpile(&program, Target::JavaScript);
+        assert!(out.contains("Math.trunc(a / b) : 0"));
+    }
+
+    #[test]
+    fn lol_idiv_and_lol_imod_truncate_toward_zero_like_rust() {
+        // python's own `//`/`%` floor toward negative infinity, so this
+        // pins the shim helpers' sign behavior against rust's `/`/`%`
+        // (what `interpreter.rs` actually runs QUOSHUNT OF/MOD OF through)
+        // for a case where floor and truncate disagree
+        fn lol_idiv(a: i64, b: i64) -> i64 {
+            let q = (a.abs()) / (b.abs());
+            if (a < 0) != (b < 0) {
+                -q
+            } else {
+                q
+            }
+        }
+        fn lol_imod(a: i64, b: i64) -> i64 {
+            a - b * lol_idiv(a, b)
+        }
+        assert_eq!(lol_idiv(-7, 2), -7 / 2);
+        assert_eq!(lol_imod(-7, 2), -7 % 2);
+    }
+}
@@ -1,8 +1,10 @@
 // types: token definitions for lolcode lexical analysis
 // represents all token types with position tracking
 
+use serde::Serialize;
+
 /// represents different kinds of tokens in lolcode
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum TokenKind {
     Keyword(String),
     Identifier(String),
@@ -13,64 +15,48 @@ pub enum TokenKind {
 }
 
 /// token with kind and position information for error reporting
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct Token {
     pub kind: TokenKind,
     pub line: usize,
     pub column: usize,
+    /// byte offset range this token occupies in the source
+    pub start_byte: usize,
+    pub end_byte: usize,
 }
 
+/// every word this lexer tokenizes as `Keyword`; shared so `is_keyword`
+/// and anything that needs the full list (e.g. the lsp's completion
+/// provider) can't drift apart into two hand-maintained copies
+pub const KEYWORDS: &[&str] = &[
+    "HAI", "KTHXBYE", "VISIBLE", "GIMMEH", "I", "HAS", "A", "ITZ", "R", "AN", "SUM", "OF", "DIFF",
+    "PRODUKT", "QUOSHUNT", "MOD", "BOTH", "SAEM", "DIFFRINT", "O", "RLY?", "YA", "RLY", "MEBBE",
+    "NO", "WAI", "OIC", "IM", "IN", "YR", "LOOP", "UPPIN", "NERFIN", "TIL", "WILE", "HOW", "DUZ",
+    "FOUND", "MKAY", "OBTW", "TLDR", "OUTTA", "GTFO",
+];
+
 impl Token {
     /// creates a new token with position information
-    pub fn new(kind: TokenKind, line: usize, column: usize) -> Self {
-        Self { kind, line, column }
+    pub fn new(kind: TokenKind, line: usize, column: usize, start_byte: usize, end_byte: usize) -> Self {
+        Self { kind, line, column, start_byte, end_byte }
     }
 
     /// checks if a word is a lolcode keyword
     pub fn is_keyword(word: &str) -> bool {
-        matches!(
-            word,
-            "HAI"
-                | "KTHXBYE"
-                | "VISIBLE"
-                | "GIMMEH"
-                | "I"
-                | "HAS"
-                | "A"
-                | "ITZ"
-                | "R"
-                | "AN"
-                | "SUM"
-                | "OF"
-                | "DIFF"
-                | "PRODUKT"
-                | "QUOSHUNT"
-                | "MOD"
-                | "BOTH"
-                | "SAEM"
-                | "DIFFRINT"
-                | "O"
-                | "RLY?"
-                | "YA"
-                | "RLY"
-                | "MEBBE"
-                | "NO"
-                | "WAI"
-                | "OIC"
-                | "IM"
-                | "IN"
-                | "YR"
-                | "LOOP"
-                | "UPPIN"
-                | "NERFIN"
-                | "TIL"
-                | "WILE"
-                | "HOW"
-                | "DUZ"
-                | "FOUND"
-                | "MKAY"
-                | "OBTW"
-                | "TLDR"
-        )
+        KEYWORDS.contains(&word)
+    }
+
+    /// lolcode keywords and builtins that are not tokenized as `Keyword`
+    /// today but are reserved in real lolcode; identifiers matching one of
+    /// these are flagged by the linter instead of silently shadowing them
+    pub fn is_near_keyword(word: &str) -> bool {
+        matches!(word, "IT" | "WIN" | "FAIL" | "OMG" | "OMGWTF" | "NOOB")
+    }
+
+    /// reserved lolcode names that must never be treated as ordinary,
+    /// user-assignable variables (the implicit `IT` and the boolean
+    /// literals `WIN`/`FAIL`)
+    pub fn is_reserved_name(word: &str) -> bool {
+        matches!(word, "IT" | "WIN" | "FAIL")
     }
 }
@@ -0,0 +1,181 @@
+// upgrade: `lol-lint upgrade <file.lol>...`, a built-in codemod that
+// migrates a file's `HAI` header from 1.2 to 1.3 and applies
+// `DEPRECATED_RULES` -- any 1.2-only construct 1.3 has since replaced --
+// via the same `codemod` engine `rewrite` drives from a rules file
+//
+// this parser doesn't actually branch its grammar on the header version
+// (see `parser::parse_program`'s version handling -- it's read and
+// stored, never consulted again), so there's currently no lolcode
+// construct in this crate that 1.3 deprecates and 1.2 doesn't. the rule
+// list below is real infrastructure for the day one exists, not a stub;
+// today it's honestly empty, and `upgrade` says so rather than
+// fabricating a migration this crate's grammar has no use for
+
+use lol_lint::codemod;
+use lol_lint::lexer::Lexer;
+use lol_lint::parser::Parser;
+use lol_lint::types::TokenKind;
+use std::collections::HashMap;
+
+const TARGET_VERSION: &str = "1.3";
+
+/// `(match pattern, replace template)` pairs applied to every file, the
+/// same shape a `rewrite` rules file's `[[rule]]` table parses into
+const DEPRECATED_RULES: &[(&str, &str)] = &[];
+
+pub fn run(args: &[String]) -> i32 {
+    if args.is_empty() {
+        eprintln!("usage: lol-lint upgrade <file.lol>...");
+        return 2;
+    }
+
+    let mut had_error = false;
+    for file in args {
+        match upgrade_file(file) {
+            Ok(changed) => {
+                if changed {
+                    println!("upgraded {file} to {TARGET_VERSION}");
+                } else {
+                    println!("{file} is already up to date");
+                }
+            }
+            Err(e) => {
+                eprintln!("error: {e}");
+                had_error = true;
+            }
+        }
+    }
+
+    if DEPRECATED_RULES.is_empty() {
+        eprintln!(
+            "note: no 1.2-only constructs found to migrate -- this parser's grammar doesn't vary by HAI version"
+        );
+    }
+
+    if had_error {
+        2
+    } else {
+        0
+    }
+}
+
+fn upgrade_file(file: &str) -> Result<bool, String> {
+    let mut source = std::fs::read_to_string(file).map_err(|e| format!("could not read '{file}': {e}"))?;
+    let mut changed = bump_header_version(&mut source)?;
+
+    for (pattern_text, replace_text) in DEPRECATED_RULES {
+        let pattern = codemod::parse_pattern(pattern_text)
+            .map_err(|e| format!("built-in upgrade rule '{pattern_text}' failed to parse: {e}"))?;
+        let replace = codemod::parse_pattern(replace_text)
+            .map_err(|e| format!("built-in upgrade rule '{replace_text}' failed to parse: {e}"))?;
+
+        loop {
+            let tokens = Lexer::new(source.clone()).tokenize();
+            let mut parser = Parser::new(tokens);
+            let program = parser.parse_program();
+
+            let mut found: Option<(usize, usize, HashMap<String, String>)> = None;
+            codemod::visit_expressions(&program, &mut |candidate| {
+                if found.is_some() {
+                    return;
+                }
+                if let Some(bindings) = codemod::match_pattern(&pattern, candidate) {
+                    let (start, end) = codemod::expr_span(candidate);
+                    let bindings_text = bindings
+                        .into_iter()
+                        .map(|(name, expr)| {
+                            let (bstart, bend) = codemod::expr_span(expr);
+                            (name, source[bstart..bend].to_string())
+                        })
+                        .collect();
+                    found = Some((start, end, bindings_text));
+                }
+            });
+
+            let Some((start, end, bindings_text)) = found else {
+                break;
+            };
+            let replacement = codemod::render_replacement(&replace, &bindings_text)?;
+            source = format!("{}{}{}", &source[..start], replacement, &source[end..]);
+            changed = true;
+        }
+    }
+
+    if changed {
+        std::fs::write(file, &source).map_err(|e| format!("could not write '{file}': {e}"))?;
+    }
+    Ok(changed)
+}
+
+/// finds the `HAI` header's version number token, if any, and rewrites
+/// it in place to `TARGET_VERSION`; a header with no version number
+/// (bare `HAI`) gets one inserted, since `1.3` is the version being
+/// upgraded to either way
+fn bump_header_version(source: &mut String) -> Result<bool, String> {
+    let tokens = Lexer::new(source.clone()).tokenize();
+    let mut hai_end = None;
+    let mut version_span = None;
+    for (i, token) in tokens.iter().enumerate() {
+        if matches!(&token.kind, TokenKind::Keyword(k) if k == "HAI") {
+            hai_end = Some(token.end_byte);
+            if let Some(next) = tokens.get(i + 1) {
+                if let TokenKind::Number(n) = &next.kind {
+                    if n == TARGET_VERSION {
+                        return Ok(false);
+                    }
+                    version_span = Some((next.start_byte, next.end_byte));
+                }
+            }
+            break;
+        }
+    }
+
+    let Some(hai_end) = hai_end else {
+        return Err("no HAI header found".to_string());
+    };
+
+    match version_span {
+        Some((start, end)) => {
+            source.replace_range(start..end, TARGET_VERSION);
+        }
+        None => {
+            source.insert_str(hai_end, &format!(" {TARGET_VERSION}"));
+        }
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bumps_a_1_2_header_to_1_3() {
+        let mut source = "HAI 1.2\nVISIBLE \"hi\"\nKTHXBYE\n".to_string();
+        let changed = bump_header_version(&mut source).unwrap();
+        assert!(changed);
+        assert!(source.starts_with("HAI 1.3\n"));
+    }
+
+    #[test]
+    fn a_header_already_on_the_target_version_is_left_unchanged() {
+        let mut source = "HAI 1.3\nVISIBLE \"hi\"\nKTHXBYE\n".to_string();
+        let changed = bump_header_version(&mut source).unwrap();
+        assert!(!changed);
+        assert_eq!(source, "HAI 1.3\nVISIBLE \"hi\"\nKTHXBYE\n");
+    }
+
+    #[test]
+    fn a_bare_hai_with_no_version_gets_one_inserted() {
+        let mut source = "HAI\nVISIBLE \"hi\"\nKTHXBYE\n".to_string();
+        let changed = bump_header_version(&mut source).unwrap();
+        assert!(changed);
+        assert!(source.starts_with("HAI 1.3\n"));
+    }
+
+    #[test]
+    fn a_missing_hai_header_is_an_error() {
+        let mut source = "VISIBLE \"hi\"\nKTHXBYE\n".to_string();
+        assert!(bump_header_version(&mut source).is_err());
+    }
+}
@@ -0,0 +1,26 @@
+// wasm: a wasm-bindgen entry point so a browser playground or the vs code
+// web extension can run the linter client-side instead of shelling out to
+// the `lol-lint` binary, which isn't an option in either environment
+//
+// only this crate's `--lib` target builds for wasm32-unknown-unknown: the
+// `lol-lint` binary pulls in rayon and colored, neither of which target
+// wasm32-unknown-unknown, but the library itself never depends on them.
+// build with:
+//
+//     cargo build --target wasm32-unknown-unknown --lib --features wasm
+
+use wasm_bindgen::prelude::*;
+
+use crate::config::LintConfig;
+
+/// lints `source` and returns its diagnostics as a json-encoded
+/// [`crate::LintResult`]. `config_json` is a json-encoded [`LintConfig`];
+/// an empty string or invalid json falls back to [`LintConfig::default`]
+/// rather than failing the whole call, since most callers just want the
+/// default rule set and shouldn't have to serialize one to get it
+#[wasm_bindgen]
+pub fn lint(source: &str, config_json: &str) -> Result<String, JsValue> {
+    let config = serde_json::from_str(config_json).unwrap_or_else(|_| LintConfig::default());
+    let result = crate::lint_source(source, &config);
+    serde_json::to_string(&result).map_err(|e| JsValue::from_str(&e.to_string()))
+}